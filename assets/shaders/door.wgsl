@@ -1,9 +1,33 @@
 #import bevy_sprite::mesh2d_vertex_output::VertexOutput
+#import bevy_sprite::mesh2d_view_bindings::globals
+
+struct DoorRim {
+    color: vec4<f32>,
+    width: f32,
+    highlight_radius: f32,
+    focus: f32,
+    pulse_rate: f32,
+}
 
 @group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> fill_color: vec4<f32>;
-@group(#{MATERIAL_BIND_GROUP}) @binding(1) var<uniform> player_position: vec2<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var<storage, read> player_position: vec2<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var<uniform> rim: DoorRim;
+@group(#{MATERIAL_BIND_GROUP}) @binding(3) var<uniform> size: vec2<f32>;
+
+const TAU: f32 = 6.28318530718;
 
 @fragment
 fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
-    return fill_color;
+    // Distance in world units to the closest edge of the door
+    let edge = min(mesh.uv, vec2(1.0) - mesh.uv) * size;
+    let outline = 1.0 - smoothstep(rim.width * 0.5, rim.width, min(edge.x, edge.y));
+
+    let player_distance = distance(mesh.world_position.xy, player_position);
+    let proximity = 1.0 - smoothstep(0.0, rim.highlight_radius, player_distance);
+
+    // Subtle pulse between 80% and 100% of the intensity while focused
+    let pulse = 0.9 + 0.1 * sin(globals.time * rim.pulse_rate * TAU);
+    let intensity = mix(proximity, pulse, rim.focus);
+
+    return mix(fill_color, rim.color, outline * intensity);
 }
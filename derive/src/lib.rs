@@ -1,11 +1,195 @@
 use proc_macro::TokenStream;
-use syn::{DeriveInput, parse_macro_input};
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{Data, DeriveInput, Fields, Ident, Path, parse_macro_input};
 
-#[proc_macro_derive(SectorMessage)]
-pub fn derive_sector_message(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, .. } = parse_macro_input!(input);
-    let output = quote::quote! {
-        impl SectorMessage for #ident {}
+/// Fields of `TriggerContext` that can be copied into a message.
+const CONTEXT_FIELDS: [&str; 7] = [
+    "transition",
+    "trigger",
+    "target",
+    "distance",
+    "angle",
+    "contact_point",
+    "parameters",
+];
+
+/// Implements `TriggerMessage`, and for structs with named fields also `From<TriggerContext>`.
+///
+/// Fields named after a field of `TriggerContext` are copied from it, a field can also be mapped
+/// to a differently named context field with `#[trigger(context_field)]`, which fails to compile if
+/// there is no such context field. Every other field is filled with its [`Default`] value.
+///
+/// The generated code refers to the sector module as `crate::sector`, so outside of the game crate
+/// the path of the crate has to be given with `#[trigger(crate = path)]` on the struct.
+#[proc_macro_derive(TriggerMessage, attributes(trigger))]
+pub fn derive_trigger_message(input: TokenStream) -> TokenStream {
+    expand_trigger_message(parse_macro_input!(input)).into()
+}
+
+fn expand_trigger_message(input: DeriveInput) -> TokenStream2 {
+    let DeriveInput {
+        ident, data, attrs, ..
+    } = input;
+
+    let mut crate_path: Path = syn::parse_quote!(crate);
+    for attribute in attrs.iter().filter(|a| a.path().is_ident("trigger")) {
+        let result = attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                crate_path = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate = path`"))
+            }
+        });
+        if let Err(error) = result {
+            return error.to_compile_error();
+        }
+    }
+
+    let constructor = match data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => {
+                let mut field_values = Vec::new();
+
+                for field in fields.named {
+                    let name = field.ident.expect("Named fields always have an identifier");
+
+                    let mut context_field = None;
+                    for attribute in field.attrs.iter().filter(|a| a.path().is_ident("trigger")) {
+                        match attribute.parse_args::<Ident>() {
+                            Ok(mapped) => context_field = Some(mapped),
+                            Err(error) => return error.to_compile_error(),
+                        }
+                    }
+
+                    let value = match context_field {
+                        Some(mapped) if CONTEXT_FIELDS.contains(&mapped.to_string().as_str()) => {
+                            quote::quote! { context.#mapped }
+                        }
+                        Some(mapped) => {
+                            return syn::Error::new_spanned(
+                                &mapped,
+                                format!(
+                                    "`TriggerContext` has no field `{mapped}`, expected one of: {}",
+                                    CONTEXT_FIELDS.join(", ")
+                                ),
+                            )
+                            .to_compile_error();
+                        }
+                        None if CONTEXT_FIELDS.contains(&name.to_string().as_str()) => {
+                            quote::quote! { context.#name }
+                        }
+                        None => quote::quote! { Default::default() },
+                    };
+                    field_values.push(quote::quote! { #name: #value });
+                }
+
+                Some(quote::quote! {
+                    impl From<#crate_path::sector::TriggerContext> for #ident {
+                        fn from(context: #crate_path::sector::TriggerContext) -> Self {
+                            Self { #(#field_values),* }
+                        }
+                    }
+                })
+            }
+            _ => None,
+        },
+        _ => None,
     };
-    output.into()
+
+    quote::quote! {
+        impl #crate_path::sector::TriggerMessage for #ident {}
+        #constructor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(input: DeriveInput) -> String {
+        expand_trigger_message(input).to_string()
+    }
+
+    #[test]
+    fn copies_fields_named_after_context_fields() {
+        let output = expand(syn::parse_quote! {
+            struct Message {
+                target: Entity,
+                distance: f32,
+            }
+        });
+
+        assert!(output.contains("target : context . target"), "{output}");
+        assert!(output.contains("distance : context . distance"), "{output}");
+    }
+
+    #[test]
+    fn maps_fields_to_other_context_fields() {
+        let output = expand(syn::parse_quote! {
+            struct Message {
+                #[trigger(target)]
+                door: Entity,
+            }
+        });
+
+        assert!(output.contains("door : context . target"), "{output}");
+    }
+
+    #[test]
+    fn defaults_unannotated_fields_that_are_not_context_fields() {
+        let output = expand(syn::parse_quote! {
+            struct Message {
+                count: u32,
+            }
+        });
+
+        assert!(output.contains("count : Default :: default ()"), "{output}");
+    }
+
+    #[test]
+    fn rejects_mappings_to_unknown_context_fields() {
+        let output = expand(syn::parse_quote! {
+            struct Message {
+                #[trigger(targte)]
+                door: Entity,
+            }
+        });
+
+        assert!(output.contains("compile_error"), "{output}");
+        assert!(output.contains("no field `targte`"), "{output}");
+        assert!(!output.contains("impl"), "{output}");
+    }
+
+    #[test]
+    fn uses_the_crate_path_of_the_attribute() {
+        let output = expand(syn::parse_quote! {
+            #[trigger(crate = ::game)]
+            struct Message {
+                target: Entity,
+            }
+        });
+
+        assert!(
+            output.contains(":: game :: sector :: TriggerMessage"),
+            "{output}"
+        );
+        assert!(
+            output.contains(":: game :: sector :: TriggerContext"),
+            "{output}"
+        );
+        assert!(!output.contains("crate :: sector"), "{output}");
+    }
+
+    #[test]
+    fn rejects_unknown_struct_attributes() {
+        let output = expand(syn::parse_quote! {
+            #[trigger(krate = ::game)]
+            struct Message {
+                target: Entity,
+            }
+        });
+
+        assert!(output.contains("compile_error"), "{output}");
+    }
 }
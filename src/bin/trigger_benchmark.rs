@@ -0,0 +1,200 @@
+//! Measures how long a fixed tick takes with a large number of shape triggers.
+//!
+//! Run with `cargo run --release --bin trigger_benchmark`. The physics only scenario is the
+//! baseline that the trigger scenarios should be compared against.
+
+use std::{f32::consts::PI, time::Duration};
+
+use avian2d::prelude::*;
+use bevy::{platform::time::Instant, prelude::*, scene::ScenePlugin, time::TimeUpdateStrategy};
+use topdown_controller_2d::sector::{
+    Sector, ShapeQueryBudget, ShapeTrigger, TriggerChannel, TriggerContext, TriggerMessage,
+    TriggerOcclusion, TriggerPlugin,
+};
+
+const TIMESTEP: Duration = Duration::from_micros(15625);
+const WARMUP_TICKS: u32 = 10;
+const MEASURED_TICKS: u32 = 200;
+
+const TRIGGER_COUNT: usize = 1000;
+const TARGET_COUNT: usize = 300;
+const OBSTACLE_COUNT: usize = 200;
+/// Size of the square area everything is spawned in.
+const AREA_SIZE: f32 = 2000.0;
+
+const TARGET_LAYER: LayerMask = LayerMask(0b01);
+const OBSTACLE_LAYER: LayerMask = LayerMask(0b10);
+
+#[derive(Message)]
+struct BenchmarkMessage;
+
+impl From<TriggerContext> for BenchmarkMessage {
+    fn from(_: TriggerContext) -> Self {
+        Self
+    }
+}
+
+impl TriggerMessage for BenchmarkMessage {}
+
+#[derive(Resource, Clone)]
+struct Scenario {
+    name: &'static str,
+    trigger_count: usize,
+    update_interval: u32,
+    budget: Option<Duration>,
+    moving_triggers: bool,
+    moving_targets: bool,
+}
+
+impl Scenario {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            trigger_count: TRIGGER_COUNT,
+            update_interval: 1,
+            budget: None,
+            moving_triggers: false,
+            moving_targets: false,
+        }
+    }
+}
+
+#[derive(Component)]
+struct Spinning;
+
+fn main() {
+    let scenarios = [
+        Scenario {
+            trigger_count: 0,
+            moving_targets: true,
+            ..Scenario::new("physics only")
+        },
+        Scenario::new("stationary"),
+        Scenario {
+            moving_targets: true,
+            ..Scenario::new("moving targets")
+        },
+        Scenario {
+            moving_triggers: true,
+            moving_targets: true,
+            ..Scenario::new("moving triggers and targets")
+        },
+        Scenario {
+            update_interval: 4,
+            moving_triggers: true,
+            moving_targets: true,
+            ..Scenario::new("moving, every 4 ticks")
+        },
+        Scenario {
+            budget: Some(Duration::from_millis(1)),
+            moving_triggers: true,
+            moving_targets: true,
+            ..Scenario::new("moving, 1 ms budget")
+        },
+    ];
+
+    println!(
+        "{TRIGGER_COUNT} triggers, {TARGET_COUNT} targets, {OBSTACLE_COUNT} obstacles, \
+         {MEASURED_TICKS} ticks"
+    );
+    for scenario in scenarios {
+        let tick_time = run(scenario.clone());
+        println!(
+            "{:<30} {:>8.3} ms per tick",
+            scenario.name,
+            tick_time.as_secs_f64() * 1000.0
+        );
+    }
+}
+
+/// Average duration of a single fixed tick in the scenario.
+fn run(scenario: Scenario) -> Duration {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        AssetPlugin::default(),
+        // Collider constructors of avian depend on scenes
+        ScenePlugin,
+        PhysicsPlugins::default(),
+        TriggerPlugin::<BenchmarkMessage>::default(),
+    ))
+    .init_asset::<Mesh>()
+    // Exactly one fixed tick per update
+    .insert_resource(TimeUpdateStrategy::ManualDuration(TIMESTEP))
+    .insert_resource(Time::<Fixed>::from_duration(TIMESTEP))
+    .insert_resource(ShapeQueryBudget(scenario.budget))
+    .insert_resource(scenario)
+    .add_systems(Startup, spawn_scene)
+    .add_systems(FixedUpdate, spin_triggers);
+
+    app.finish();
+    app.cleanup();
+
+    for _ in 0..WARMUP_TICKS {
+        app.update();
+    }
+
+    let start = Instant::now();
+    for _ in 0..MEASURED_TICKS {
+        app.update();
+    }
+    start.elapsed() / MEASURED_TICKS
+}
+
+/// Deterministic and evenly spread position for the `i`-th object of a kind.
+fn scatter(i: usize, seed: f32) -> Vec2 {
+    let t = i as f32 + seed;
+    (vec2((t * 0.618_034).fract(), (t * 0.754_878).fract()) - 0.5) * AREA_SIZE
+}
+
+fn spawn_scene(scenario: Res<Scenario>, mut commands: Commands) {
+    for i in 0..scenario.trigger_count {
+        let trigger = commands
+            .spawn((
+                Sector::new(75.0, PI * 0.5, 0.0, 8.0),
+                ShapeTrigger::new()
+                    .with_channel(TriggerChannel::new::<BenchmarkMessage>(TARGET_LAYER))
+                    .with_occlusion(TriggerOcclusion::new(OBSTACLE_LAYER))
+                    .with_update_interval(scenario.update_interval),
+                Transform::from_translation(scatter(i, 0.0).extend(0.0))
+                    .with_rotation(Quat::from_rotation_z(i as f32)),
+            ))
+            .id();
+
+        if scenario.moving_triggers {
+            commands.entity(trigger).insert(Spinning);
+        }
+    }
+
+    for i in 0..TARGET_COUNT {
+        let velocity = if scenario.moving_targets {
+            Vec2::from_angle(i as f32) * 50.0
+        } else {
+            Vec2::ZERO
+        };
+
+        commands.spawn((
+            RigidBody::Kinematic,
+            Collider::circle(8.0),
+            CollisionLayers::new(TARGET_LAYER, LayerMask::ALL),
+            LinearVelocity(velocity),
+            Transform::from_translation(scatter(i, 0.3).extend(0.0)),
+        ));
+    }
+
+    for i in 0..OBSTACLE_COUNT {
+        commands.spawn((
+            RigidBody::Static,
+            Collider::rectangle(20.0, 20.0),
+            CollisionLayers::new(OBSTACLE_LAYER, LayerMask::ALL),
+            Transform::from_translation(scatter(i, 0.7).extend(0.0)),
+        ));
+    }
+}
+
+fn spin_triggers(time: Res<Time>, mut triggers: Query<&mut Transform, With<Spinning>>) {
+    for mut transform in &mut triggers {
+        transform.rotate_z(time.delta_secs());
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use crate::objects::characters::Player;
+
+mod bounds;
+mod pixel_perfect;
+mod shake;
+mod zoom;
+
+pub use bounds::*;
+pub use pixel_perfect::*;
+pub use shake::*;
+pub use zoom::*;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((CameraBoundsPlugin, CameraShakePlugin, CameraZoomPlugin))
+            .configure_sets(
+                FixedPostUpdate,
+                (
+                    CameraSystems::Tracking,
+                    CameraSystems::Confinement,
+                    CameraSystems::Effects,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                FixedPostUpdate,
+                camera_tracking.in_set(CameraSystems::Tracking),
+            );
+    }
+}
+
+/// Ordering of the systems that position the player camera each fixed tick.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CameraSystems {
+    /// Moves the camera onto the player.
+    Tracking,
+    /// Keeps the camera view inside the active camera region.
+    Confinement,
+    /// Offsets applied on top of the confined position, such as camera shake.
+    Effects,
+}
+
+#[derive(Component)]
+pub struct PlayerCamera;
+
+#[allow(clippy::type_complexity)]
+fn camera_tracking(
+    player: Single<&Transform, With<Player>>,
+    camera: Single<(&mut Transform, Option<&CameraZoom>), (With<PlayerCamera>, Without<Player>)>,
+) {
+    let (mut transform, zoom) = camera.into_inner();
+    let zoom_offset = zoom.map_or(Vec2::ZERO, |zoom| zoom.offset);
+    transform.translation = player.translation + zoom_offset.extend(0.0);
+}
@@ -0,0 +1,398 @@
+use bevy::{camera::CameraProjection, prelude::*};
+
+use crate::{
+    camera::{CameraSystems, PlayerCamera},
+    objects::characters::Player,
+    sector::polygon_contains_point,
+};
+
+/// Number of corner pushes used to fit the camera view inside a polygon region.
+const POLYGON_CONFINE_ITERATIONS: usize = 4;
+
+pub struct CameraBoundsPlugin;
+
+impl Plugin for CameraBoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraLevelBounds>().add_systems(
+            FixedPostUpdate,
+            confine_camera.in_set(CameraSystems::Confinement),
+        );
+    }
+}
+
+/// World-space rectangle the camera view is kept inside whenever the player is not inside any
+/// [`CameraRegion`].
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct CameraLevelBounds(pub Option<Rect>);
+
+#[derive(Clone, Debug)]
+pub enum CameraRegionShape {
+    Rectangle(Rect),
+    Polygon(Vec<Vec2>),
+}
+
+/// World-space area (usually a room) that the camera view is kept inside while the player is
+/// inside of it.
+#[derive(Component, Clone, Debug)]
+pub struct CameraRegion {
+    pub shape: CameraRegionShape,
+}
+
+impl CameraRegion {
+    pub fn rectangle(rect: Rect) -> Self {
+        Self {
+            shape: CameraRegionShape::Rectangle(rect),
+        }
+    }
+
+    /// Creates a region from the vertices of a simple polygon, in either winding order.
+    pub fn polygon(vertices: Vec<Vec2>) -> Self {
+        Self {
+            shape: CameraRegionShape::Polygon(vertices),
+        }
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        match &self.shape {
+            CameraRegionShape::Rectangle(rect) => rect.contains(point),
+            CameraRegionShape::Polygon(vertices) => polygon_contains_point(vertices, point),
+        }
+    }
+
+    /// Moves a camera view centered at `center` with the given half size so that it lies inside
+    /// of the region. Axes on which the view is larger than the region are centered instead.
+    ///
+    /// * `center` - World-space center of the camera view
+    /// * `half_size` - Half of the world-space size of the camera view
+    pub fn confine(&self, center: Vec2, half_size: Vec2) -> Vec2 {
+        match &self.shape {
+            CameraRegionShape::Rectangle(rect) => confine_to_rect(center, half_size, *rect),
+            CameraRegionShape::Polygon(vertices) => confine_to_polygon(center, half_size, vertices),
+        }
+    }
+}
+
+/// Per-camera state of the region confinement, including the blend between regions.
+#[derive(Component)]
+pub struct CameraConfinement {
+    /// Time in seconds it takes to blend from one region to the next.
+    pub blend_duration: f32,
+    current_region: Option<Entity>,
+    last_position: Option<Vec2>,
+    blend_offset: Vec2,
+    blend_progress: f32,
+}
+
+impl CameraConfinement {
+    pub fn new(blend_duration: f32) -> Self {
+        Self {
+            blend_duration,
+            current_region: None,
+            last_position: None,
+            blend_offset: Vec2::ZERO,
+            blend_progress: 1.0,
+        }
+    }
+
+    /// The region the camera is currently confined to, or [`None`] if it falls back to the
+    /// [`CameraLevelBounds`].
+    pub fn current_region(&self) -> Option<Entity> {
+        self.current_region
+    }
+}
+
+impl Default for CameraConfinement {
+    fn default() -> Self {
+        Self::new(0.4)
+    }
+}
+
+fn confine_to_rect(center: Vec2, half_size: Vec2, rect: Rect) -> Vec2 {
+    let min = rect.min + half_size;
+    let max = rect.max - half_size;
+
+    vec2(
+        if min.x <= max.x {
+            center.x.clamp(min.x, max.x)
+        } else {
+            rect.center().x
+        },
+        if min.y <= max.y {
+            center.y.clamp(min.y, max.y)
+        } else {
+            rect.center().y
+        },
+    )
+}
+
+fn closest_point_on_polygon(vertices: &[Vec2], point: Vec2) -> Vec2 {
+    let mut closest = point;
+    let mut closest_distance = f32::INFINITY;
+
+    for (i, a) in vertices.iter().enumerate() {
+        let b = vertices[(i + 1) % vertices.len()];
+        let edge = b - *a;
+        let t = ((point - *a).dot(edge) / edge.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+        let candidate = *a + edge * t;
+
+        let distance = candidate.distance_squared(point);
+        if distance < closest_distance {
+            closest = candidate;
+            closest_distance = distance;
+        }
+    }
+
+    closest
+}
+
+fn confine_to_polygon(center: Vec2, half_size: Vec2, vertices: &[Vec2]) -> Vec2 {
+    if vertices.len() < 3 {
+        return center;
+    }
+
+    let bounds = vertices.iter().fold(
+        Rect::from_center_size(vertices[0], Vec2::ZERO),
+        |rect, v| rect.union_point(*v),
+    );
+    let mut center = confine_to_rect(center, half_size, bounds);
+
+    // Axes where the view doesn't fit are already centered by the bounding box and must not be
+    // pushed around by the corners.
+    let free_axes = vec2(
+        (bounds.half_size().x > half_size.x) as u8 as f32,
+        (bounds.half_size().y > half_size.y) as u8 as f32,
+    );
+
+    // Corners that stick out of the polygon are pushed back onto its boundary. This is exact for
+    // convex rooms and a close approximation for concave ones.
+    for _ in 0..POLYGON_CONFINE_ITERATIONS {
+        let mut pushed = false;
+
+        for corner_sign in [
+            vec2(-1.0, -1.0),
+            vec2(1.0, -1.0),
+            vec2(1.0, 1.0),
+            vec2(-1.0, 1.0),
+        ] {
+            let corner = center + corner_sign * half_size;
+            if !polygon_contains_point(vertices, corner) {
+                center += (closest_point_on_polygon(vertices, corner) - corner) * free_axes;
+                pushed = true;
+            }
+        }
+
+        if !pushed {
+            break;
+        }
+    }
+
+    center
+}
+
+/// Half of the world-space size of the camera view at the current projection scale.
+/// `OrthographicProjection::area` is only updated in `PostUpdate`, so it still has the scale of the
+/// previous frame right after zooming.
+fn view_half_size(camera: &Camera, projection: &OrthographicProjection) -> Option<Vec2> {
+    let viewport = camera
+        .logical_viewport_size()
+        .filter(|size| size.x > 0.0 && size.y > 0.0)?;
+
+    let mut projection = projection.clone();
+    projection.update(viewport.x, viewport.y);
+    Some(projection.area.half_size())
+}
+
+/// Region the player is in. The current region is kept until the player actually leaves it, so
+/// that overlapping regions (e.g. around doorways) don't make the camera flicker between them.
+fn player_region(
+    current_region: Option<Entity>,
+    regions: &[(Entity, &CameraRegion)],
+    player_position: Vec2,
+) -> Option<Entity> {
+    let still_inside = regions.iter().any(|(entity, region)| {
+        Some(*entity) == current_region && region.contains(player_position)
+    });
+    if still_inside {
+        return current_region;
+    }
+
+    regions
+        .iter()
+        .find(|(_, region)| region.contains(player_position))
+        .map(|(entity, _)| *entity)
+}
+
+/// Clamps the camera target into the region the player is in. The target itself may be offset
+/// from the player, e.g. by zooming towards the cursor, which doesn't change the region.
+#[allow(clippy::type_complexity)]
+fn confine_camera(
+    time: Res<Time>,
+    level_bounds: Res<CameraLevelBounds>,
+    regions: Query<(Entity, &CameraRegion)>,
+    player: Single<&Transform, (With<Player>, Without<PlayerCamera>)>,
+    camera: Single<
+        (&mut Transform, &mut CameraConfinement, &Camera, &Projection),
+        With<PlayerCamera>,
+    >,
+) {
+    let (mut transform, mut confinement, camera, projection) = camera.into_inner();
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+    let Some(half_size) = view_half_size(camera, projection) else {
+        return;
+    };
+    let target = transform.translation.xy();
+
+    let confine = |region: Option<Entity>| match region.and_then(|entity| regions.get(entity).ok())
+    {
+        Some((_, region)) => region.confine(target, half_size),
+        None => level_bounds.map_or(target, |rect| confine_to_rect(target, half_size, rect)),
+    };
+
+    let next_region = player_region(
+        confinement.current_region,
+        &regions.iter().collect::<Vec<_>>(),
+        player.translation.xy(),
+    );
+
+    if next_region != confinement.current_region {
+        confinement.current_region = next_region;
+
+        // Blend from wherever the camera currently is, which also handles switching regions
+        // again in the middle of a blend.
+        if let Some(last_position) = confinement.last_position {
+            confinement.blend_offset = last_position - confine(next_region);
+            confinement.blend_progress = 0.0;
+        }
+    }
+
+    if confinement.blend_progress < 1.0 {
+        confinement.blend_progress = (confinement.blend_progress
+            + time.delta_secs() / confinement.blend_duration.max(f32::EPSILON))
+        .min(1.0);
+    }
+
+    let blend = 1.0 - confinement.blend_progress;
+    // Smoothstep of the remaining blend
+    let blend_weight = blend * blend * (3.0 - 2.0 * blend);
+    let position = confine(confinement.current_region) + confinement.blend_offset * blend_weight;
+
+    confinement.last_position = Some(position);
+    transform.translation = position.extend(transform.translation.z);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// L-shaped room, concave at its top right.
+    fn l_shape() -> Vec<Vec2> {
+        vec![
+            vec2(0.0, 0.0),
+            vec2(200.0, 0.0),
+            vec2(200.0, 100.0),
+            vec2(100.0, 100.0),
+            vec2(100.0, 200.0),
+            vec2(0.0, 200.0),
+        ]
+    }
+
+    #[test]
+    fn polygon_regions_contain_points_inside() {
+        let region = CameraRegion::polygon(l_shape());
+
+        assert!(region.contains(vec2(50.0, 50.0)));
+        assert!(region.contains(vec2(150.0, 50.0)));
+        assert!(region.contains(vec2(50.0, 150.0)));
+    }
+
+    #[test]
+    fn polygon_regions_do_not_contain_points_outside() {
+        let region = CameraRegion::polygon(l_shape());
+
+        assert!(!region.contains(vec2(150.0, 150.0)));
+        assert!(!region.contains(vec2(-10.0, 50.0)));
+        assert!(!region.contains(vec2(50.0, 250.0)));
+    }
+
+    #[test]
+    fn polygon_regions_ignore_winding_order() {
+        let mut vertices = l_shape();
+        vertices.reverse();
+        let region = CameraRegion::polygon(vertices);
+
+        assert!(region.contains(vec2(150.0, 50.0)));
+        assert!(!region.contains(vec2(150.0, 150.0)));
+    }
+
+    #[test]
+    fn confine_to_rect_keeps_views_that_fit() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 100.0);
+
+        assert_eq!(
+            confine_to_rect(vec2(50.0, 50.0), Vec2::splat(10.0), rect),
+            vec2(50.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn confine_to_rect_clamps_views_to_the_edges() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 100.0);
+
+        assert_eq!(
+            confine_to_rect(vec2(-20.0, 95.0), Vec2::splat(10.0), rect),
+            vec2(10.0, 90.0)
+        );
+    }
+
+    #[test]
+    fn confine_to_rect_centers_axes_larger_than_the_rect() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 100.0);
+
+        assert_eq!(
+            confine_to_rect(vec2(20.0, 20.0), vec2(80.0, 10.0), rect),
+            vec2(50.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn confine_to_polygon_pushes_corners_back_inside() {
+        let vertices = l_shape();
+        let half_size = Vec2::splat(20.0);
+        let center = confine_to_polygon(vec2(95.0, 250.0), half_size, &vertices);
+
+        for corner in [
+            vec2(-1.0, -1.0),
+            vec2(1.0, -1.0),
+            vec2(1.0, 1.0),
+            vec2(-1.0, 1.0),
+        ] {
+            let corner = center + corner * half_size * 0.99;
+            assert!(
+                polygon_contains_point(&vertices, corner),
+                "{corner} is outside"
+            );
+        }
+    }
+
+    #[test]
+    fn player_region_is_kept_while_the_player_is_inside_of_it() {
+        let [west, east] = [1, 2].map(|index| Entity::from_raw_u32(index).unwrap());
+        let west_region = CameraRegion::rectangle(Rect::new(0.0, 0.0, 100.0, 100.0));
+        let east_region = CameraRegion::rectangle(Rect::new(90.0, 0.0, 200.0, 100.0));
+        let regions = [(west, &west_region), (east, &east_region)];
+
+        assert_eq!(player_region(None, &regions, vec2(50.0, 50.0)), Some(west));
+        // Both regions contain the doorway
+        assert_eq!(
+            player_region(Some(east), &regions, vec2(95.0, 50.0)),
+            Some(east)
+        );
+        assert_eq!(
+            player_region(Some(west), &regions, vec2(150.0, 50.0)),
+            Some(east)
+        );
+        assert_eq!(player_region(Some(west), &regions, vec2(300.0, 50.0)), None);
+    }
+}
@@ -0,0 +1,267 @@
+use avian2d::prelude::*;
+use bevy::{
+    camera::{RenderTarget, visibility::RenderLayers},
+    prelude::*,
+    render::render_resource::{
+        Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    },
+    transform::TransformSystems,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::camera::{CameraZoom, PlayerCamera};
+
+/// Render layers of the world, which is drawn to the low resolution canvas.
+pub const PIXEL_PERFECT_LAYERS: RenderLayers = RenderLayers::layer(0);
+
+/// Render layers of the upscaled canvas, which is drawn to the window.
+pub const HIGH_RES_LAYERS: RenderLayers = RenderLayers::layer(1);
+
+/// Extra canvas pixels on every side so that sub-pixel smoothing never shows the canvas edge.
+const CANVAS_MARGIN: u32 = 1;
+
+/// Renders the world to a low resolution canvas which is then upscaled to the window by an
+/// integer factor. Sprites and the player camera are snapped to the canvas pixel grid so that
+/// pixel art doesn't shimmer while moving.
+///
+/// Snapping only changes the `GlobalTransform` of sprites after propagation. Physics would copy
+/// the snapped position back into the simulation, so sprites on rigid bodies and colliders are
+/// left alone and should be put on a child entity instead.
+pub struct PixelPerfectPlugin {
+    upscale: u32,
+    sub_pixel_smoothing: bool,
+}
+
+impl PixelPerfectPlugin {
+    pub fn new(upscale: u32) -> Self {
+        Self {
+            upscale: upscale.max(1),
+            sub_pixel_smoothing: true,
+        }
+    }
+
+    pub fn with_sub_pixel_smoothing(self, sub_pixel_smoothing: bool) -> Self {
+        Self {
+            sub_pixel_smoothing,
+            ..self
+        }
+    }
+}
+
+impl Default for PixelPerfectPlugin {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl Plugin for PixelPerfectPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PixelPerfectSettings {
+            upscale: self.upscale,
+            sub_pixel_smoothing: self.sub_pixel_smoothing,
+        })
+        .add_systems(PostStartup, setup_pixel_perfect_camera)
+        .add_systems(Update, fit_canvas)
+        .add_systems(
+            PostUpdate,
+            snap_to_canvas_grid.after(TransformSystems::Propagate),
+        );
+    }
+}
+
+#[derive(Resource, Clone, Copy)]
+pub struct PixelPerfectSettings {
+    /// Number of window pixels every canvas pixel is scaled up to.
+    pub upscale: u32,
+    /// Whether the fractional part of the camera position is applied when upscaling, which keeps
+    /// camera motion smooth while the world itself stays on the pixel grid.
+    pub sub_pixel_smoothing: bool,
+}
+
+/// Low resolution image the world is rendered to.
+#[derive(Resource)]
+pub struct PixelPerfectCanvas {
+    pub image: Handle<Image>,
+    pub size: UVec2,
+    upscale: u32,
+    offset: Vec2,
+}
+
+impl PixelPerfectCanvas {
+    /// Converts a window position (e.g. the cursor) into a viewport position of the player
+    /// camera, which renders to the canvas instead of the window.
+    ///
+    /// * `window_position` - Logical position in the window, from the top left corner
+    /// * `window_size` - Logical size of the window
+    pub fn window_to_viewport(&self, window_position: Vec2, window_size: Vec2) -> Vec2 {
+        let from_center = (window_position - window_size / 2.0) / self.upscale as f32;
+        // The canvas is shifted by the sub-pixel offset, which is in y-up canvas pixels
+        from_center + vec2(self.offset.x, -self.offset.y) + self.size.as_vec2() / 2.0
+    }
+}
+
+/// Sprite that displays the [`PixelPerfectCanvas`] in the window.
+#[derive(Component)]
+struct Canvas;
+
+/// Camera that renders the upscaled [`Canvas`] to the window.
+#[derive(Component)]
+struct CanvasCamera;
+
+fn canvas_size(window: &Window, upscale: u32) -> UVec2 {
+    (window.size() / upscale as f32).ceil().as_uvec2() + UVec2::splat(2 * CANVAS_MARGIN)
+}
+
+fn setup_pixel_perfect_camera(
+    settings: Res<PixelPerfectSettings>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(Entity, &mut Camera, &Projection, Option<&mut CameraZoom>), With<PlayerCamera>>,
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = canvas_size(&window, settings.upscale);
+    let canvas_extent = Extent3d {
+        width: size.x,
+        height: size.y,
+        ..default()
+    };
+
+    let mut canvas = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("pixel_perfect_canvas"),
+            size: canvas_extent,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    canvas.resize(canvas_extent);
+    let image = images.add(canvas);
+
+    let (camera_entity, mut camera, projection, zoom) = camera.into_inner();
+    // Render the world before the canvas camera draws it to the window
+    camera.order = -1;
+    commands.entity(camera_entity).insert((
+        RenderTarget::Image(image.clone().into()),
+        Msaa::Off,
+        PIXEL_PERFECT_LAYERS,
+    ));
+
+    // Zoom out by the upscale factor so that the same area of the world stays visible
+    if let (Projection::Orthographic(projection), Some(mut zoom)) = (projection, zoom) {
+        let scale = projection.scale;
+        zoom.zoom_to(scale, scale * settings.upscale as f32, None);
+    }
+
+    commands.spawn((Sprite::from_image(image.clone()), Canvas, HIGH_RES_LAYERS));
+    commands.spawn((
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scale: 1.0 / settings.upscale as f32,
+            ..OrthographicProjection::default_2d()
+        }),
+        Msaa::Off,
+        CanvasCamera,
+        HIGH_RES_LAYERS,
+    ));
+
+    commands.insert_resource(PixelPerfectCanvas {
+        image,
+        size,
+        upscale: settings.upscale,
+        offset: Vec2::ZERO,
+    });
+}
+
+fn fit_canvas(
+    settings: Res<PixelPerfectSettings>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    canvas: Option<ResMut<PixelPerfectCanvas>>,
+    mut resize_messages: MessageReader<WindowResized>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(mut canvas) = canvas else {
+        return;
+    };
+    if resize_messages.read().last().is_none() {
+        return;
+    }
+
+    let size = canvas_size(&window, settings.upscale);
+    if size == canvas.size {
+        return;
+    }
+
+    if let Some(image) = images.get_mut(&canvas.image) {
+        image.resize(Extent3d {
+            width: size.x,
+            height: size.y,
+            ..default()
+        });
+    }
+    canvas.size = size;
+}
+
+#[allow(clippy::type_complexity)]
+fn snap_to_canvas_grid(
+    settings: Res<PixelPerfectSettings>,
+    canvas: Option<ResMut<PixelPerfectCanvas>>,
+    mut camera: Single<(&mut GlobalTransform, &Projection), With<PlayerCamera>>,
+    canvas_camera: Single<(&mut Transform, &mut GlobalTransform), With<CanvasCamera>>,
+    mut sprites: Query<
+        &mut GlobalTransform,
+        (
+            With<Sprite>,
+            Without<RigidBody>,
+            Without<Collider>,
+            Without<Canvas>,
+            Without<PlayerCamera>,
+            Without<CanvasCamera>,
+        ),
+    >,
+) {
+    let Some(mut canvas) = canvas else {
+        return;
+    };
+    let (camera_transform, projection) = &mut *camera;
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+
+    // World units covered by a single canvas pixel
+    let pixel_size = projection.scale;
+    let snap = |translation: Vec3| {
+        ((translation.xy() / pixel_size).round() * pixel_size).extend(translation.z)
+    };
+
+    let mut affine = camera_transform.affine();
+    let translation = Vec3::from(affine.translation);
+    let snapped = snap(translation);
+    affine.translation = snapped.into();
+    **camera_transform = GlobalTransform::from(affine);
+
+    // The canvas camera works in canvas pixels, so moving it by the remainder of the world camera
+    // shifts the whole canvas by less than a pixel.
+    canvas.offset = if settings.sub_pixel_smoothing {
+        (translation - snapped).xy() / pixel_size
+    } else {
+        Vec2::ZERO
+    };
+    // Transforms have already been propagated this frame, so both are updated to avoid lagging a
+    // frame behind the world camera.
+    let (mut canvas_transform, mut canvas_global_transform) = canvas_camera.into_inner();
+    canvas_transform.translation = canvas.offset.extend(canvas_transform.translation.z);
+    *canvas_global_transform = GlobalTransform::from(*canvas_transform);
+
+    for mut global_transform in &mut sprites {
+        let mut affine = global_transform.affine();
+        affine.translation = snap(affine.translation.into()).into();
+        *global_transform = GlobalTransform::from(affine);
+    }
+}
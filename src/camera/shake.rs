@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+
+use crate::camera::{CameraSystems, PlayerCamera};
+
+pub struct CameraShakePlugin;
+
+impl Plugin for CameraShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraShakeScale>()
+            .add_message::<CameraTrauma>()
+            .add_systems(
+                FixedPostUpdate,
+                (add_camera_trauma, apply_camera_shake)
+                    .chain()
+                    .in_set(CameraSystems::Effects),
+            );
+    }
+}
+
+/// Global multiplier applied to every camera shake, e.g. to tone it down or disable it entirely
+/// as an accessibility option.
+#[derive(Resource, Deref, DerefMut)]
+pub struct CameraShakeScale(pub f32);
+
+impl Default for CameraShakeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Adds trauma to every shaking camera. Trauma is clamped to `[0, 1]` and the shake intensity is
+/// the square of the trauma, so small amounts barely register while large amounts stack up fast.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct CameraTrauma(pub f32);
+
+#[derive(Component, Clone, Debug)]
+pub struct CameraShake {
+    /// Current trauma in `[0, 1]`.
+    pub trauma: f32,
+    /// Amount of trauma removed per second.
+    pub decay: f32,
+    /// Translation offset in world units at full trauma.
+    pub max_offset: Vec2,
+    /// Rotation offset in radians at full trauma.
+    pub max_rotation: f32,
+    /// Speed at which the noise is sampled, higher values give a more violent shake.
+    pub frequency: f32,
+    seed: f32,
+    elapsed: f32,
+}
+
+impl CameraShake {
+    pub fn new(decay: f32, max_offset: Vec2, max_rotation: f32, frequency: f32) -> Self {
+        Self {
+            trauma: 0.0,
+            decay,
+            max_offset,
+            max_rotation,
+            frequency,
+            seed: 0.0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn with_seed(self, seed: f32) -> Self {
+        Self { seed, ..self }
+    }
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Removes the trauma that decays over `delta` seconds.
+    fn decay_trauma(&mut self, delta: f32) {
+        self.trauma = (self.trauma - self.decay * delta).max(0.0);
+    }
+
+    /// Translation and rotation offset of the camera at the current trauma, with the intensity
+    /// multiplied by `scale`.
+    fn offset(&self, scale: f32) -> (Vec2, f32) {
+        let intensity = self.trauma * self.trauma * scale.max(0.0);
+        let t = self.elapsed * self.frequency;
+
+        // Each channel samples the noise at a different offset so they move independently
+        let offset = vec2(
+            perlin_noise(self.seed + t),
+            perlin_noise(self.seed + 100.0 + t),
+        ) * self.max_offset
+            * intensity;
+        let rotation = perlin_noise(self.seed + 200.0 + t) * self.max_rotation * intensity;
+
+        (offset, rotation)
+    }
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self::new(1.0, Vec2::splat(20.0), 0.05, 15.0)
+    }
+}
+
+/// Hashes a lattice point into a gradient in `[-1, 1]`.
+fn gradient(lattice: i32) -> f32 {
+    let mut hash = (lattice as u32).wrapping_mul(0x9E37_79B9);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 13;
+
+    (hash as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+/// One dimensional gradient (Perlin) noise in roughly `[-1, 1]`.
+fn perlin_noise(x: f32) -> f32 {
+    let lattice = x.floor();
+    let t = x - lattice;
+    let fade = t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+
+    let a = gradient(lattice as i32) * t;
+    let b = gradient(lattice as i32 + 1) * (t - 1.0);
+
+    // Gradient noise peaks at 0.5 along each dimension
+    2.0 * (a + (b - a) * fade)
+}
+
+fn add_camera_trauma(
+    mut trauma_messages: MessageReader<CameraTrauma>,
+    mut cameras: Query<&mut CameraShake>,
+) {
+    for CameraTrauma(amount) in trauma_messages.read() {
+        for mut shake in &mut cameras {
+            shake.add_trauma(*amount);
+        }
+    }
+}
+
+fn apply_camera_shake(
+    time: Res<Time>,
+    scale: Res<CameraShakeScale>,
+    mut cameras: Query<(&mut Transform, &mut CameraShake), With<PlayerCamera>>,
+) {
+    for (mut transform, mut shake) in &mut cameras {
+        shake.elapsed += time.delta_secs();
+
+        let (offset, rotation) = shake.offset(**scale);
+
+        // The tracking systems overwrite the translation every tick, so the offset is added on top
+        // of the followed position. The rotation is only ever set by the shake.
+        transform.translation += offset.extend(0.0);
+        transform.rotation = Quat::from_rotation_z(rotation);
+
+        shake.decay_trauma(time.delta_secs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_close;
+
+    #[test]
+    fn trauma_is_clamped_to_one() {
+        let mut shake = CameraShake::default();
+        shake.add_trauma(0.7);
+        shake.add_trauma(0.7);
+        assert_eq!(shake.trauma, 1.0);
+
+        shake.add_trauma(-3.0);
+        assert_eq!(shake.trauma, 0.0);
+    }
+
+    #[test]
+    fn trauma_decays_linearly_down_to_zero() {
+        let mut shake = CameraShake::new(0.5, Vec2::splat(20.0), 0.05, 15.0);
+        shake.add_trauma(0.8);
+
+        shake.decay_trauma(1.0);
+        assert_close(shake.trauma, 0.3);
+
+        shake.decay_trauma(1.0);
+        assert_eq!(shake.trauma, 0.0);
+    }
+
+    #[test]
+    fn shake_offset_is_reset_without_trauma() {
+        // Sampled in between two lattice points of the noise, where it isn't zero
+        let mut shake = CameraShake {
+            elapsed: 0.37,
+            ..default()
+        };
+        assert_eq!(shake.offset(1.0), (Vec2::ZERO, 0.0));
+
+        shake.add_trauma(1.0);
+        let (offset, rotation) = shake.offset(1.0);
+        assert!(offset != Vec2::ZERO && rotation != 0.0);
+
+        shake.decay_trauma(2.0);
+        assert_eq!(shake.offset(1.0), (Vec2::ZERO, 0.0));
+    }
+
+    #[test]
+    fn shake_offset_is_scaled_by_the_shake_scale() {
+        let mut shake = CameraShake {
+            elapsed: 0.37,
+            ..default()
+        };
+        shake.add_trauma(1.0);
+
+        let (offset, rotation) = shake.offset(1.0);
+        let (half_offset, half_rotation) = shake.offset(0.5);
+        assert_close(half_offset.x, offset.x * 0.5);
+        assert_close(half_offset.y, offset.y * 0.5);
+        assert_close(half_rotation, rotation * 0.5);
+        assert_eq!(shake.offset(0.0), (Vec2::ZERO, 0.0));
+    }
+}
@@ -0,0 +1,369 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+
+use crate::mouse_cache::MouseCache;
+
+/// Zoom per scrolled line, a scrolled pixel counts as `1 / PIXELS_PER_LINE` lines so that mouse
+/// wheels and trackpads zoom by the same amount for the same physical scroll distance.
+#[derive(Resource, Deref, DerefMut)]
+pub struct CameraZoomSensitivity(pub f32);
+
+impl Default for CameraZoomSensitivity {
+    fn default() -> Self {
+        Self(0.1)
+    }
+}
+
+/// Approximate number of pixels a trackpad reports for a single mouse wheel line.
+const PIXELS_PER_LINE: f32 = 40.0;
+
+/// Keys that jump to the corresponding entry of [`CameraZoom::presets`].
+const PRESET_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+pub struct CameraZoomPlugin;
+
+impl Plugin for CameraZoomPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraZoomSensitivity>()
+            .add_observer(seed_camera_zoom)
+            .add_systems(
+                Update,
+                (
+                    camera_zoom_input,
+                    camera_zoom_presets,
+                    animate_camera_zoom,
+                    recenter_camera_zoom,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct CameraZoom {
+    /// Smallest allowed projection scale (most zoomed in).
+    pub min_scale: f32,
+    /// Largest allowed projection scale (most zoomed out).
+    pub max_scale: f32,
+    /// Projection scales that can be jumped to with the number keys.
+    pub presets: Vec<f32>,
+    /// Time in seconds it takes to ease into a new zoom level.
+    pub duration: f32,
+    /// Whether the world point under the cursor stays fixed while zooming.
+    pub zoom_to_cursor: bool,
+    /// World units per sprite texel. When set, zoom levels snap to scales where every texel
+    /// covers a whole number of screen pixels (or the other way around when zoomed out).
+    pub texel_size: Option<f32>,
+    /// Offset of the camera from its tracked position caused by zooming towards the cursor.
+    pub offset: Vec2,
+    /// Rate per second at which the offset decays back to the tracked position once the zoom
+    /// settles, the offset shrinks by a factor of `e` every `1 / recenter_rate` seconds.
+    pub recenter_rate: f32,
+    start_scale: f32,
+    target_scale: f32,
+    progress: f32,
+    anchor: Option<Vec2>,
+}
+
+impl CameraZoom {
+    pub fn new(min_scale: f32, max_scale: f32) -> Self {
+        Self {
+            min_scale,
+            max_scale,
+            presets: Vec::new(),
+            duration: 0.15,
+            zoom_to_cursor: true,
+            texel_size: None,
+            offset: Vec2::ZERO,
+            recenter_rate: 1.5,
+            start_scale: 1.0,
+            target_scale: 1.0,
+            progress: 1.0,
+            anchor: None,
+        }
+    }
+
+    pub fn with_presets(self, presets: Vec<f32>) -> Self {
+        Self { presets, ..self }
+    }
+
+    pub fn with_duration(self, duration: f32) -> Self {
+        Self { duration, ..self }
+    }
+
+    pub fn with_zoom_to_cursor(self, zoom_to_cursor: bool) -> Self {
+        Self {
+            zoom_to_cursor,
+            ..self
+        }
+    }
+
+    pub fn with_recenter_rate(self, recenter_rate: f32) -> Self {
+        Self {
+            recenter_rate,
+            ..self
+        }
+    }
+
+    pub fn with_texel_size(self, texel_size: f32) -> Self {
+        Self {
+            texel_size: Some(texel_size),
+            ..self
+        }
+    }
+
+    /// Whether the zoom has finished easing towards its target.
+    pub fn is_settled(&self) -> bool {
+        self.progress >= 1.0
+    }
+
+    /// Projection scale the zoom is currently easing towards.
+    pub fn target_scale(&self) -> f32 {
+        self.target_scale
+    }
+
+    /// Starts easing from `current_scale` to `target_scale`, clamped to the zoom limits and
+    /// snapped to the texel grid.
+    ///
+    /// * `current_scale` - Projection scale the animation starts from
+    /// * `target_scale` - Requested projection scale
+    /// * `anchor` - World position that should stay fixed on screen, if any
+    pub fn zoom_to(&mut self, current_scale: f32, target_scale: f32, anchor: Option<Vec2>) {
+        self.start_scale = current_scale;
+        self.target_scale = self.snap(target_scale.clamp(self.min_scale, self.max_scale));
+        self.progress = 0.0;
+        self.anchor = anchor.filter(|_| self.zoom_to_cursor);
+    }
+
+    /// Snaps a scale within the zoom limits to the closest scale within the limits at which every
+    /// texel covers a whole number of screen pixels, or every screen pixel a whole number of
+    /// texels. Scales are kept as they are if there is no such scale within the limits.
+    fn snap(&self, scale: f32) -> f32 {
+        let Some(texel_size) = self.texel_size.filter(|texel_size| *texel_size > 0.0) else {
+            return scale;
+        };
+
+        // Screen pixels per texel at this scale
+        let pixels_per_texel = texel_size / scale;
+        // The closest allowed scales below and above the scale
+        let candidates = if pixels_per_texel >= 1.0 {
+            [
+                texel_size / pixels_per_texel.ceil(),
+                texel_size / pixels_per_texel.floor(),
+            ]
+        } else {
+            let texels_per_pixel = scale / texel_size;
+            [
+                texel_size * texels_per_pixel.floor(),
+                texel_size * texels_per_pixel.ceil(),
+            ]
+        };
+
+        // Allowed scales outside of the limits are skipped in favor of the other one, which is
+        // the closest allowed scale within the limits as `scale` is within them.
+        candidates
+            .into_iter()
+            .filter(|candidate| (self.min_scale..=self.max_scale).contains(candidate))
+            .min_by(|a, b| (a - scale).abs().total_cmp(&(b - scale).abs()))
+            .unwrap_or(scale)
+    }
+}
+
+impl Default for CameraZoom {
+    fn default() -> Self {
+        Self::new(0.1, 5.0).with_presets(vec![0.5, 1.0, 2.0])
+    }
+}
+
+/// Starts the zoom of a camera at the scale its projection already has.
+fn seed_camera_zoom(add: On<Add, CameraZoom>, mut cameras: Query<(&mut CameraZoom, &Projection)>) {
+    let Ok((mut zoom, Projection::Orthographic(projection))) = cameras.get_mut(add.entity) else {
+        return;
+    };
+
+    zoom.start_scale = projection.scale;
+    zoom.target_scale = projection.scale;
+}
+
+fn camera_zoom_input(
+    sensitivity: Res<CameraZoomSensitivity>,
+    mouse_cache: Res<MouseCache>,
+    mut scroll_input: MessageReader<MouseWheel>,
+    camera: Single<(&mut CameraZoom, &Projection)>,
+) {
+    let (mut zoom, projection) = camera.into_inner();
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+
+    let lines: f32 = scroll_input
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        })
+        .sum();
+    if lines == 0.0 {
+        return;
+    }
+
+    // Zooming is multiplicative so that each scroll step feels the same at every zoom level.
+    // Scrolls during an animation continue from the target so that they accumulate.
+    let target_scale = zoom.target_scale() * (-lines * **sensitivity).exp();
+    zoom.zoom_to(projection.scale, target_scale, mouse_cache.world_position());
+}
+
+fn camera_zoom_presets(
+    key_input: Res<ButtonInput<KeyCode>>,
+    camera: Single<(&mut CameraZoom, &Projection)>,
+) {
+    let (mut zoom, projection) = camera.into_inner();
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+
+    for (key, preset) in PRESET_KEYS.iter().zip(zoom.presets.clone()) {
+        if key_input.just_pressed(*key) {
+            zoom.zoom_to(projection.scale, preset, None);
+        }
+    }
+}
+
+fn animate_camera_zoom(
+    time: Res<Time>,
+    camera: Single<(&mut CameraZoom, &mut Projection, &Transform)>,
+) {
+    let (mut zoom, mut projection, transform) = camera.into_inner();
+    let Projection::Orthographic(projection) = projection.as_mut() else {
+        return;
+    };
+
+    if zoom.is_settled() {
+        return;
+    }
+
+    zoom.progress = (zoom.progress + time.delta_secs() / zoom.duration.max(f32::EPSILON)).min(1.0);
+    // Ease out cubic
+    let eased = 1.0 - (1.0 - zoom.progress).powi(3);
+
+    let previous_scale = projection.scale;
+    projection.scale = zoom.start_scale + (zoom.target_scale - zoom.start_scale) * eased;
+
+    if let Some(anchor) = zoom.anchor {
+        // Moving towards the anchor by the relative change in scale keeps it at the same spot on
+        // the screen.
+        let center = transform.translation.xy();
+        zoom.offset += (anchor - center) * (1.0 - projection.scale / previous_scale);
+        // Never zoom so far off that the tracked target leaves the view
+        let half_size = projection.area.half_size() * projection.scale / previous_scale;
+        zoom.offset = zoom.offset.clamp(-half_size, half_size);
+    }
+}
+
+/// Moves the camera back onto its tracked position once it stops zooming.
+fn recenter_camera_zoom(time: Res<Time>, mut zoom: Single<&mut CameraZoom>) {
+    if !zoom.is_settled() || zoom.offset == Vec2::ZERO {
+        return;
+    }
+
+    let offset = zoom.offset * (-zoom.recenter_rate * time.delta_secs()).exp();
+    // Snap back once the remaining offset is less than a tenth of a world unit
+    zoom.offset = if offset.length_squared() < 0.01 {
+        Vec2::ZERO
+    } else {
+        offset
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_close;
+
+    fn zoom(min_scale: f32, max_scale: f32, texel_size: f32) -> CameraZoom {
+        CameraZoom::new(min_scale, max_scale).with_texel_size(texel_size)
+    }
+
+    #[test]
+    fn zoom_starts_at_the_scale_of_the_projection() {
+        let mut world = World::new();
+        world.add_observer(seed_camera_zoom);
+        let camera = world
+            .spawn((
+                Projection::Orthographic(OrthographicProjection {
+                    scale: 2.5,
+                    ..OrthographicProjection::default_2d()
+                }),
+                CameraZoom::new(0.1, 5.0),
+            ))
+            .id();
+
+        let mut zoom = world.get_mut::<CameraZoom>(camera).unwrap();
+        assert_eq!(zoom.target_scale(), 2.5);
+
+        // Scrolling continues from the target, so it zooms relative to the projection scale
+        let target_scale = zoom.target_scale() * 0.5;
+        zoom.zoom_to(2.5, target_scale, None);
+        assert_close(zoom.target_scale(), 1.25);
+    }
+
+    #[test]
+    fn snap_without_texel_size_keeps_the_scale() {
+        assert_eq!(CameraZoom::new(0.1, 5.0).snap(1.37), 1.37);
+    }
+
+    #[test]
+    fn snap_zoomed_in_picks_the_closest_whole_number_of_pixels_per_texel() {
+        assert_close(zoom(0.1, 5.0, 2.0).snap(0.9), 1.0);
+        assert_close(zoom(0.1, 5.0, 2.0).snap(0.7), 2.0 / 3.0);
+    }
+
+    #[test]
+    fn snap_zoomed_out_picks_the_closest_whole_number_of_texels_per_pixel() {
+        assert_close(zoom(0.1, 10.0, 2.0).snap(4.5), 4.0);
+        assert_close(zoom(0.1, 10.0, 2.0).snap(5.5), 6.0);
+    }
+
+    #[test]
+    fn snap_keeps_allowed_scales() {
+        assert_close(zoom(0.1, 10.0, 2.0).snap(2.0), 2.0);
+        assert_close(zoom(0.1, 10.0, 2.0).snap(0.5), 0.5);
+    }
+
+    #[test]
+    fn snap_stays_below_a_max_scale_larger_than_the_texel_size() {
+        // Rounding would give 7.02, the largest multiple within the limits is 4.68
+        assert_close(zoom(0.1, 6.0, 2.34).snap(6.0), 4.68);
+    }
+
+    #[test]
+    fn snap_stays_above_a_min_scale_larger_than_the_texel_size() {
+        // Rounding would give 2.34, the smallest multiple within the limits is 4.68
+        assert_close(zoom(3.0, 10.0, 2.34).snap(3.0), 4.68);
+    }
+
+    #[test]
+    fn snap_stays_within_limits_smaller_than_the_texel_size() {
+        // 2.0 / 5 = 0.4 is below the limit
+        assert_close(zoom(0.45, 5.0, 2.0).snap(0.45), 0.5);
+        // 2.0 / 2 = 1.0 is above the limit
+        assert_close(zoom(0.1, 0.9, 2.0).snap(0.9), 2.0 / 3.0);
+    }
+
+    #[test]
+    fn snap_keeps_the_scale_without_allowed_scales_within_the_limits() {
+        assert_close(zoom(2.5, 3.5, 2.0).snap(3.0), 3.0);
+    }
+}
@@ -1,11 +1,11 @@
 use bevy::prelude::*;
 
-mod camera_zoom;
 mod fps_overlay;
+mod signal_gizmos;
 mod window_esc;
 
-pub use camera_zoom::*;
 pub use fps_overlay::*;
+pub use signal_gizmos::*;
 pub use window_esc::*;
 
 pub struct DebugPlugin;
@@ -13,8 +13,8 @@ pub struct DebugPlugin;
 impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
-            CameraZoomPlugin,
             FpsOverlayPlugin::new(Color::srgb(1.0, 1.0, 0.0)),
+            SignalGizmosPlugin,
             WindowEscapePlugin,
         ));
     }
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use crate::signal::{SignalEmitter, SignalGate, SignalReceiver, Signals};
+
+const SIGNAL_GIZMOS_KEY: KeyCode = KeyCode::F3;
+const SIGNAL_ON_COLOR: Color = Color::srgb(0.2, 1.0, 0.3);
+const SIGNAL_OFF_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+
+/// Draws a line from every signal emitter to every gate and receiver listening to its channel,
+/// toggled with F3.
+pub struct SignalGizmosPlugin;
+
+impl Plugin for SignalGizmosPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShowSignalGizmos>()
+            .add_systems(Update, (toggle_signal_gizmos, draw_signal_gizmos).chain());
+    }
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ShowSignalGizmos(pub bool);
+
+fn toggle_signal_gizmos(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut show_gizmos: ResMut<ShowSignalGizmos>,
+) {
+    if key_input.just_pressed(SIGNAL_GIZMOS_KEY) {
+        **show_gizmos = !**show_gizmos;
+    }
+}
+
+fn draw_signal_gizmos(
+    show_gizmos: Res<ShowSignalGizmos>,
+    signals: Res<Signals>,
+    emitters: Query<(&SignalEmitter, &GlobalTransform)>,
+    gates: Query<(&SignalGate, &GlobalTransform)>,
+    receivers: Query<(&SignalReceiver, &GlobalTransform)>,
+    mut gizmos: Gizmos,
+) {
+    if !**show_gizmos {
+        return;
+    }
+
+    let color = |channel: &str| {
+        if signals.is_on(channel) {
+            SIGNAL_ON_COLOR
+        } else {
+            SIGNAL_OFF_COLOR
+        }
+    };
+
+    for (_, transform) in &gates {
+        gizmos.rect_2d(
+            transform.translation().xy(),
+            Vec2::splat(12.0),
+            Color::WHITE,
+        );
+    }
+
+    for (emitter, emitter_transform) in &emitters {
+        let start = emitter_transform.translation().xy();
+        let color = color(&emitter.channel);
+
+        let gate_inputs = gates
+            .iter()
+            .filter(|(gate, _)| gate.inputs.contains(&emitter.channel))
+            .map(|(_, transform)| transform);
+        let receivers = receivers
+            .iter()
+            .filter(|(receiver, _)| receiver.channel == emitter.channel)
+            .map(|(_, transform)| transform);
+
+        for end in gate_inputs.chain(receivers) {
+            gizmos.arrow_2d(start, end.translation().xy(), color);
+        }
+    }
+}
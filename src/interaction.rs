@@ -0,0 +1,308 @@
+//! Focus and interaction between characters and the objects around them.
+//!
+//! Object types plug in by requiring an [`Interactable`], e.g.
+//! `#[require(Interactable = Interactable::new("Pull"))]`, and reading the [`Interacted`] messages
+//! targeting them through [`InteractionsWith`].
+
+use bevy::{
+    ecs::{entity::EntityHashMap, system::SystemParam},
+    prelude::*,
+};
+
+use crate::sector::{
+    TriggerContext, TriggerDetectionSystems, TriggerMessage, TriggerPlugin, TriggerSelection,
+    TriggerTransition,
+};
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(TriggerPlugin::<InteractionMessage>::default())
+            .add_message::<Interacted>()
+            .add_systems(
+                FixedUpdate,
+                (track_interaction_candidates, update_focus)
+                    .chain()
+                    .after(TriggerDetectionSystems),
+            );
+    }
+}
+
+/// Systems that write [`Interacted`] messages, readers should run after this set.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InteractionSystems;
+
+/// Object that characters can focus and interact with.
+#[derive(Component, Clone, Debug)]
+pub struct Interactable {
+    /// Text shown to the player while the object is focused.
+    pub prompt: String,
+    /// Higher priorities are focused over lower ones regardless of where they are.
+    pub priority: i32,
+}
+
+impl Interactable {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            priority: 0,
+        }
+    }
+
+    pub fn with_priority(self, priority: i32) -> Self {
+        Self { priority, ..self }
+    }
+}
+
+/// Character that focuses interactables through a trigger with an [`InteractionMessage`] channel,
+/// either on its own entity or on one of its children.
+///
+/// The candidates with the highest priority are narrowed down with [`Interactor::selection`]
+/// rather than a selection of the channel, which would drop candidates before their priority is
+/// known.
+#[derive(Component)]
+pub struct Interactor {
+    /// Which of the candidates with the highest priority is focused.
+    pub selection: TriggerSelection,
+    /// Amount by which another candidate has to beat the score of the focused one to take over
+    /// the focus, see [`TriggerChannel::hysteresis`](crate::sector::TriggerChannel::hysteresis).
+    pub hysteresis: f32,
+    /// Detected interactables along with how they were detected.
+    candidates: EntityHashMap<TriggerContext>,
+    focused: Option<Entity>,
+}
+
+impl Interactor {
+    pub fn new() -> Self {
+        Self {
+            selection: TriggerSelection::ClosestAngle,
+            hysteresis: 0.1,
+            candidates: EntityHashMap::default(),
+            focused: None,
+        }
+    }
+
+    pub fn with_selection(self, selection: TriggerSelection, hysteresis: f32) -> Self {
+        Self {
+            selection,
+            hysteresis,
+            ..self
+        }
+    }
+
+    pub fn focused(&self) -> Option<Entity> {
+        self.focused
+    }
+}
+
+impl Default for Interactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Only present on the interactable an interactor is focusing.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub struct Focused {
+    pub interactor: Entity,
+}
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct Interacted {
+    pub interactor: Entity,
+    pub target: Entity,
+}
+
+/// Keeps the whole context of the detection, which the selection of the [`Interactor`] scores.
+#[derive(Message)]
+pub struct InteractionMessage(TriggerContext);
+
+impl TriggerMessage for InteractionMessage {}
+
+impl From<TriggerContext> for InteractionMessage {
+    fn from(context: TriggerContext) -> Self {
+        Self(context)
+    }
+}
+
+/// Reads the [`Interacted`] messages whose target has the component `C`.
+#[derive(SystemParam)]
+pub struct InteractionsWith<'w, 's, C: Component> {
+    messages: MessageReader<'w, 's, Interacted>,
+    targets: Query<'w, 's, (), With<C>>,
+}
+
+impl<C: Component> InteractionsWith<'_, '_, C> {
+    pub fn read(&mut self) -> impl Iterator<Item = &Interacted> {
+        let targets = &self.targets;
+        self.messages
+            .read()
+            .filter(move |message| targets.contains(message.target))
+    }
+}
+
+fn track_interaction_candidates(
+    parents: Query<&ChildOf>,
+    mut interactors: Query<&mut Interactor>,
+    mut messages: MessageReader<InteractionMessage>,
+) {
+    for InteractionMessage(context) in messages.read() {
+        // Triggers are usually children of the character they belong to
+        let interactor = match parents.get(context.trigger) {
+            Ok(child_of) if interactors.contains(child_of.parent()) => child_of.parent(),
+            _ => context.trigger,
+        };
+        let Ok(mut interactor) = interactors.get_mut(interactor) else {
+            continue;
+        };
+
+        match context.transition {
+            TriggerTransition::Enter | TriggerTransition::Stay => {
+                interactor.candidates.insert(context.target, *context);
+            }
+            TriggerTransition::Exit => {
+                interactor.candidates.remove(&context.target);
+            }
+        }
+    }
+}
+
+/// Focuses the candidate with the highest priority, and of those the one chosen by the selection of
+/// the interactor.
+fn update_focus(
+    interactables: Query<&Interactable>,
+    focused: Query<&Focused>,
+    mut interactors: Query<(Entity, &mut Interactor)>,
+    mut commands: Commands,
+) {
+    for (interactor_entity, mut interactor) in &mut interactors {
+        let priority = |target: Entity| {
+            interactables
+                .get(target)
+                .ok()
+                .map(|interactable| interactable.priority)
+        };
+        let highest_priority = interactor
+            .candidates
+            .keys()
+            .filter_map(|target| priority(*target))
+            .max();
+
+        // A focus with a lower priority than the best candidates loses it regardless of the
+        // hysteresis, as it isn't among the detections the selection chooses from
+        let best = highest_priority.and_then(|highest_priority| {
+            let candidates = interactor
+                .candidates
+                .values()
+                .filter(|context| priority(context.target) == Some(highest_priority));
+            interactor
+                .selection
+                .select(candidates, interactor.focused, interactor.hysteresis)
+        });
+
+        if best == interactor.focused {
+            continue;
+        }
+
+        if let Some(previous) = interactor.focused
+            && focused
+                .get(previous)
+                .is_ok_and(|focus| focus.interactor == interactor_entity)
+        {
+            commands.entity(previous).try_remove::<Focused>();
+        }
+        if let Some(target) = best {
+            commands.entity(target).insert(Focused {
+                interactor: interactor_entity,
+            });
+        }
+        interactor.focused = best;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::test_utils::context;
+
+    /// Runs [`update_focus`] for an interactor with the given candidates and returns its focus.
+    fn focus(
+        world: &mut World,
+        interactor: Entity,
+        candidates: &[(Entity, f32, f32)],
+    ) -> Option<Entity> {
+        world.get_mut::<Interactor>(interactor).unwrap().candidates = candidates
+            .iter()
+            .map(|(target, distance, angle)| (*target, context(*target, *distance, *angle)))
+            .collect();
+        world.run_system_once(update_focus).unwrap();
+        world.get::<Interactor>(interactor).unwrap().focused()
+    }
+
+    #[test]
+    fn focus_prefers_priority_over_the_selection() {
+        let mut world = World::new();
+        let interactor = world.spawn(Interactor::new()).id();
+        let centered = world.spawn(Interactable::new("Pull")).id();
+        let important = world.spawn(Interactable::new("Open").with_priority(1)).id();
+
+        let focused = focus(
+            &mut world,
+            interactor,
+            &[(centered, 40.0, 0.0), (important, 40.0, 0.6)],
+        );
+
+        assert_eq!(focused, Some(important));
+        assert!(world.get::<Focused>(important).is_some());
+    }
+
+    #[test]
+    fn focus_keeps_the_focused_candidate_within_the_hysteresis() {
+        let mut world = World::new();
+        let interactor = world.spawn(Interactor::new()).id();
+        let first = world.spawn(Interactable::new("Pull")).id();
+        let second = world.spawn(Interactable::new("Pull")).id();
+
+        assert_eq!(
+            focus(&mut world, interactor, &[(first, 40.0, 0.05)]),
+            Some(first)
+        );
+        // Slightly closer to the center line, which isn't enough to take over
+        assert_eq!(
+            focus(
+                &mut world,
+                interactor,
+                &[(first, 40.0, 0.05), (second, 40.0, 0.0)]
+            ),
+            Some(first)
+        );
+        // Far enough from the center line for the other candidate to take over
+        assert_eq!(
+            focus(
+                &mut world,
+                interactor,
+                &[(first, 40.0, 0.4), (second, 40.0, 0.0)]
+            ),
+            Some(second)
+        );
+        assert!(world.get::<Focused>(first).is_none());
+        assert!(world.get::<Focused>(second).is_some());
+    }
+
+    #[test]
+    fn focus_is_lost_without_candidates() {
+        let mut world = World::new();
+        let interactor = world.spawn(Interactor::new()).id();
+        let target = world.spawn(Interactable::new("Pull")).id();
+
+        focus(&mut world, interactor, &[(target, 40.0, 0.0)]);
+        let focused = focus(&mut world, interactor, &[]);
+
+        assert_eq!(focused, None);
+        assert!(world.get::<Focused>(target).is_none());
+    }
+}
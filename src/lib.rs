@@ -1,25 +1,37 @@
 // TODO: make a prelude for this crate
 
 use crate::{
+    camera::CameraPlugin,
     debug::DebugPlugin,
-    objects::{ObjectPlugin, entities::DoorMessage},
-    sector::SectorPlugin,
+    interaction::InteractionPlugin,
+    mouse_cache::MouseCachePlugin,
+    objects::ObjectPlugin,
+    persistence::PersistencePlugin,
+    signal::SignalPlugin,
     world::{WorldPlugin, WorldType},
 };
 use bevy::prelude::*;
 
+pub mod camera;
 pub mod debug;
+pub mod interaction;
 pub mod mouse_cache;
+pub mod perception;
+pub mod persistence;
 pub mod physics;
 pub mod sector;
+pub mod signal;
 pub mod world;
 
+#[cfg(test)]
+mod test_utils;
+
 pub mod objects {
     use bevy::prelude::*;
 
     use crate::objects::{
         characters::{CharacterControllerPlugin, PlayerPlugin},
-        entities::DoorPlugin,
+        entities::{DoorPlugin, KeyPlugin},
     };
 
     pub mod characters;
@@ -30,7 +42,12 @@ pub mod objects {
     // TODO: separate these into character/entity/... plugins
     impl Plugin for ObjectPlugin {
         fn build(&self, app: &mut App) {
-            app.add_plugins((CharacterControllerPlugin, PlayerPlugin, DoorPlugin));
+            app.add_plugins((
+                CharacterControllerPlugin,
+                PlayerPlugin,
+                DoorPlugin,
+                KeyPlugin,
+            ));
         }
     }
 }
@@ -88,10 +105,17 @@ pub struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
+            CameraPlugin,
             DebugPlugin,
+            MouseCachePlugin::default(),
             ObjectPlugin,
-            SectorPlugin::<DoorMessage>::default(),
+            PersistencePlugin,
+            InteractionPlugin,
+            SignalPlugin,
             WorldPlugin::new(WorldType::CustomGeometry),
         ));
+
+        #[cfg(feature = "pixel_perfect")]
+        app.add_plugins(camera::PixelPerfectPlugin::default());
     }
 }
@@ -22,7 +22,7 @@ fn main() {
                 .set(WinitPlugin::default()),
             FrameTimeDiagnosticsPlugin::default(),
             PhysicsPlugins::default().with_length_unit(200.0),
-            PhysicsDebugPlugin::default(),
+            PhysicsDebugPlugin,
             GamePlugin,
         ))
         .run();
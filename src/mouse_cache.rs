@@ -1,31 +1,120 @@
-#![allow(dead_code, unused_variables)]
-
+use avian2d::prelude::*;
 use bevy::{prelude::*, window::PrimaryWindow};
 
+use crate::camera::PixelPerfectCanvas;
+
+/// Cursor position of the primary window, cached once per frame so that systems don't each have
+/// to convert it into world space themselves.
 #[derive(Resource, Default)]
-struct MouseCache {
+pub struct MouseCache {
     window_position: Option<Vec2>,
     world_position: Option<Vec2>,
+    last_window_position: Option<Vec2>,
+    last_world_position: Option<Vec2>,
+    hovered_entities: Vec<Entity>,
+}
+
+impl MouseCache {
+    /// Logical position of the cursor in the window, or [`None`] if the cursor is outside of it.
+    pub fn window_position(&self) -> Option<Vec2> {
+        self.window_position
+    }
+
+    /// World position under the cursor as seen by the [`MouseCacheCamera`], or [`None`] if the
+    /// cursor is outside of the window.
+    pub fn world_position(&self) -> Option<Vec2> {
+        self.world_position
+    }
+
+    /// Last logical position of the cursor while it was inside the window.
+    pub fn last_window_position(&self) -> Option<Vec2> {
+        self.last_window_position
+    }
+
+    /// World position under the last known window position of the cursor. This keeps following
+    /// the camera after the cursor has left the window.
+    pub fn last_world_position(&self) -> Option<Vec2> {
+        self.last_world_position
+    }
+
+    /// Entities whose colliders contain the world position under the cursor.
+    pub fn hovered_entities(&self) -> &[Entity] {
+        &self.hovered_entities
+    }
+}
+
+/// Camera used to convert the cursor position into world space.
+#[derive(Component)]
+pub struct MouseCacheCamera;
+
+/// Layers that are considered when looking for entities under the cursor.
+#[derive(Resource, Deref, DerefMut)]
+pub struct MouseHoverMask(pub LayerMask);
+
+pub struct MouseCachePlugin {
+    hover_mask: LayerMask,
 }
 
-struct MouseCachePlugin;
+impl MouseCachePlugin {
+    pub fn new(hover_mask: LayerMask) -> Self {
+        Self { hover_mask }
+    }
+}
+
+impl Default for MouseCachePlugin {
+    fn default() -> Self {
+        Self {
+            hover_mask: LayerMask::ALL,
+        }
+    }
+}
 
 impl Plugin for MouseCachePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<MouseCache>()
-            // TODO: revisit this schedule placement
+            .insert_resource(MouseHoverMask(self.hover_mask))
+            // Runs before any game logic in `Update` reads the cache. The camera transform is the
+            // one that was rendered last frame, which is what the cursor is pointing at.
             .add_systems(PreUpdate, cache_cursor_position);
     }
 }
 
 fn cache_cursor_position(
+    hover_mask: Res<MouseHoverMask>,
+    spatial_query: Res<SpatialQueryPipeline>,
+    canvas: Option<Res<PixelPerfectCanvas>>,
     window: Single<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), With<MouseCacheCamera>>,
     mut cursor_cache: ResMut<MouseCache>,
 ) {
-    match window.cursor_position() {
-        Some(position) => {}
-        None => {
-            cursor_cache.window_position = None;
-        }
+    cursor_cache.window_position = window.cursor_position();
+    if cursor_cache.window_position.is_some() {
+        cursor_cache.last_window_position = cursor_cache.window_position;
     }
+
+    let Ok((camera, camera_transform)) = camera.single() else {
+        cursor_cache.world_position = None;
+        cursor_cache.last_world_position = None;
+        cursor_cache.hovered_entities.clear();
+        return;
+    };
+
+    let to_world = |window_position: Vec2| {
+        let viewport_position = match &canvas {
+            Some(canvas) => canvas.window_to_viewport(window_position, window.size()),
+            None => window_position,
+        };
+        camera
+            .viewport_to_world_2d(camera_transform, viewport_position)
+            .ok()
+    };
+
+    cursor_cache.world_position = cursor_cache.window_position.and_then(to_world);
+    cursor_cache.last_world_position = cursor_cache.last_window_position.and_then(to_world);
+
+    cursor_cache.hovered_entities = match cursor_cache.world_position {
+        Some(position) => spatial_query
+            .point_intersections(position, &SpatialQueryFilter::from_mask(**hover_mask)),
+        None => Vec::new(),
+    };
 }
@@ -1,5 +1,7 @@
 mod controller;
+mod inventory;
 mod player;
 
 pub use controller::*;
+pub use inventory::*;
 pub use player::*;
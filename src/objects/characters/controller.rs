@@ -21,7 +21,7 @@ pub struct CharacterControllerPlugin;
 
 impl Plugin for CharacterControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_message::<ControllerPush>().add_systems(
             FixedPreUpdate,
             (controller_movement, controller_collision_response).chain(),
         );
@@ -56,6 +56,16 @@ impl ControllerMovement {
     }
 }
 
+/// Moves a character by `displacement` on top of its own movement during the next fixed tick, e.g.
+/// when a door pushes it out of the way. The push slides along walls like the movement of the
+/// character, but passes through the `source` of the push.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct ControllerPush {
+    pub entity: Entity,
+    pub source: Entity,
+    pub displacement: Vec2,
+}
+
 fn controller_movement(
     mut controllers: Query<(&mut Transform, &mut LinearVelocity), With<CharacterController>>,
     mut movement_messages: MessageReader<ControllerMovement>,
@@ -82,7 +92,7 @@ struct CollideAndSlideData<'a> {
     transform: Transform,
     initial_velocity: LinearVelocity,
     collider: &'a Collider,
-    collision_layers: CollisionLayers,
+    filter: SpatialQueryFilter,
     fixed_delta_time: f32,
 }
 
@@ -133,7 +143,7 @@ fn collide_and_slide(
                 max_distance: cast_velocity.length() + config.skin_width,
                 ..default()
             },
-            &SpatialQueryFilter::from_mask(data.collision_layers.filters),
+            &data.filter,
         ) {
             // Maximum distance the collider can move in the direction of the cast without
             // hitting another entity.
@@ -168,18 +178,44 @@ fn collide_and_slide(
 fn controller_collision_response(
     time: Res<Time<Fixed>>,
     spatial_query: Res<SpatialQueryPipeline>,
-    mut controllers: Query<(&mut LinearVelocity, &Transform, &Collider, &CollisionLayers)>,
+    mut push_messages: MessageReader<ControllerPush>,
+    mut controllers: Query<(
+        &mut LinearVelocity,
+        &mut Transform,
+        &Collider,
+        &CollisionLayers,
+    )>,
 ) {
+    let fixed_delta_time = time.delta_secs();
+    let config = CollideAndSlideConfig::default();
+
+    // Pushes move the character directly instead of through its velocity, which would otherwise
+    // carry the push over into the next tick
+    for push in push_messages.read() {
+        let Ok((_, mut transform, collider, layers)) = controllers.get_mut(push.entity) else {
+            continue;
+        };
+
+        let data = CollideAndSlideData {
+            transform: *transform,
+            initial_velocity: LinearVelocity(push.displacement / fixed_delta_time),
+            collider,
+            filter: SpatialQueryFilter::from_mask(layers.filters)
+                .with_excluded_entities([push.source]),
+            fixed_delta_time,
+        };
+        let displacement = collide_and_slide(&data, &config, &spatial_query);
+        transform.translation += displacement.extend(0.0);
+    }
+
     for (mut velocity, transform, collider, layers) in &mut controllers {
-        let fixed_delta_time = time.delta_secs();
         let data = CollideAndSlideData {
             transform: *transform,
             initial_velocity: *velocity,
             collider,
-            collision_layers: *layers,
+            filter: SpatialQueryFilter::from_mask(layers.filters),
             fixed_delta_time,
         };
-        let config = CollideAndSlideConfig::default();
 
         let result_velocity = collide_and_slide(&data, &config, &spatial_query);
         // The result velocity is raw, and so we need to scale back up by delta time to work with
@@ -0,0 +1,22 @@
+use bevy::{platform::collections::HashSet, prelude::*};
+
+/// Items carried by a character that interacts with the world.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Inventory {
+    keys: HashSet<String>,
+}
+
+impl Inventory {
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.add_key(key);
+        self
+    }
+
+    pub fn add_key(&mut self, key: impl Into<String>) {
+        self.keys.insert(key.into());
+    }
+
+    pub fn has_key(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+}
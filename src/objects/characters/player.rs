@@ -5,19 +5,25 @@ use std::{f32::consts::PI, time::Duration};
 pub use player_shader::*;
 
 use avian2d::prelude::*;
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 
 use crate::{
-    debug::CameraZoom,
-    objects::{
-        characters::{CharacterController, ControllerMovement},
-        entities::DoorMessage,
-    },
+    camera::{CameraConfinement, CameraShake, CameraZoom, PlayerCamera},
+    interaction::{Interacted, InteractionMessage, InteractionSystems, Interactor},
+    mouse_cache::{MouseCache, MouseCacheCamera},
+    objects::characters::{CharacterController, ControllerMovement, Inventory},
     physics::{ObjectLayer, object_collision_layers},
-    sector::{Sector, SectorTrigger},
+    sector::{Sector, ShapeTrigger, TriggerChannel, TriggerOcclusion, TriggerShape},
 };
 
 const PLAYER_TEXTURE_PATH: &str = "textures/placeholders/topdown.png";
+#[cfg(not(feature = "pixel_perfect"))]
+const PLAYER_SPRITE_SIZE: f32 = 75.0;
+// Integer multiple of the texture size so that every texel covers the same number of canvas pixels
+#[cfg(feature = "pixel_perfect")]
+const PLAYER_SPRITE_SIZE: f32 = 32.0 * 2.0;
+/// World units covered by a single texel of the 32x32 player sprites.
+const PLAYER_TEXEL_SIZE: f32 = PLAYER_SPRITE_SIZE / 32.0;
 
 pub struct PlayerPlugin;
 
@@ -31,16 +37,18 @@ impl Plugin for PlayerPlugin {
                 Update,
                 (
                     player_input,
+                    player_interaction.in_set(InteractionSystems),
                     rotate_player,
                     animate_sprite::<PlayerLegs>.run_if(player_is_moving),
                 ),
-            )
-            .add_systems(FixedPostUpdate, camera_tracking);
+            );
     }
 }
 
+/// Sprite of the upper body, kept apart from the physics body so that render-only transform
+/// changes (like pixel snapping) never affect the simulation.
 #[derive(Component)]
-struct PlayerCamera;
+struct PlayerBody;
 
 #[derive(Component)]
 struct PlayerLegs;
@@ -137,18 +145,15 @@ fn player_setup(
     };
     let leg_indices = AnimationIndices { first: 0, last: 19 };
 
-    commands.spawn((Camera2d, CameraZoom, PlayerCamera));
     commands.spawn((
-        Sprite {
-            image: texture.clone(),
-            texture_atlas: Some(TextureAtlas {
-                layout: texture_atlas_layout.clone(),
-                index: player_indices.first,
-            }),
-            // TODO: find a better way of scaling sprites up
-            custom_size: Some(Vec2::splat(75.0)),
-            ..default()
-        },
+        Camera2d,
+        CameraZoom::default().with_texel_size(PLAYER_TEXEL_SIZE),
+        PlayerCamera,
+        MouseCacheCamera,
+        CameraConfinement::default(),
+        CameraShake::default(),
+    ));
+    commands.spawn((
         Collider::capsule(10.0, 25.0),
         object_collision_layers(
             vec![ObjectLayer::Player],
@@ -158,11 +163,37 @@ fn player_setup(
             .with_rotation(Quat::from_rotation_z(f32::to_radians(0.0))),
         Player::default(),
         CharacterController,
+        Inventory::default(),
+        Interactor::default(),
         children![
             (
-                SectorTrigger::<DoorMessage>::new(Sector::new(75.0, PI * 0.35, 0.0, 8.0))
-                    .with_mask(LayerMask(ObjectLayer::Door.to_bits()))
-                    .into_bundle(&mut meshes),
+                PlayerBody,
+                Sprite {
+                    image: texture.clone(),
+                    texture_atlas: Some(TextureAtlas {
+                        layout: texture_atlas_layout.clone(),
+                        index: player_indices.first,
+                    }),
+                    // TODO: find a better way of scaling sprites up
+                    custom_size: Some(Vec2::splat(PLAYER_SPRITE_SIZE)),
+                    ..default()
+                },
+                // Drawn above the legs
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            ),
+            (
+                Sector::new(75.0, PI * 0.35, 0.0, 8.0).into_bundle(&mut meshes),
+                ShapeTrigger::new()
+                    .with_channel(
+                        TriggerChannel::new::<InteractionMessage>(LayerMask(
+                            ObjectLayer::Door.to_bits() | ObjectLayer::Interactable.to_bits()
+                        ))
+                        .with_stay_messages(true)
+                    )
+                    .with_occlusion(
+                        TriggerOcclusion::new(LayerMask(ObjectLayer::Obstacle.to_bits()))
+                            .with_clipped_mesh(true)
+                    ),
             ),
             (
                 PlayerLegs,
@@ -172,7 +203,7 @@ fn player_setup(
                         layout: texture_atlas_layout,
                         index: leg_indices.first,
                     }),
-                    custom_size: Some(Vec2::splat(75.0)),
+                    custom_size: Some(Vec2::splat(PLAYER_SPRITE_SIZE)),
                     ..default()
                 },
                 leg_indices,
@@ -211,21 +242,35 @@ fn player_input(
     player_movement_event.write(ControllerMovement::from_translation(velocity, entity));
 }
 
+fn player_interaction(
+    key_input: Res<ButtonInput<KeyCode>>,
+    player: Single<(Entity, &Interactor), With<Player>>,
+    mut interactions: MessageWriter<Interacted>,
+) {
+    if !key_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let (entity, interactor) = player.into_inner();
+    if let Some(target) = interactor.focused() {
+        interactions.write(Interacted {
+            interactor: entity,
+            target,
+        });
+    }
+}
+
 fn rotate_player(
-    window: Single<&Window, With<PrimaryWindow>>,
-    camera: Single<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    mouse_cache: Res<MouseCache>,
     player_velocity: Query<&LinearVelocity, With<Player>>,
     mut legs_sprite: Query<(&mut Sprite, &AnimationIndices), With<PlayerLegs>>,
     // The QueryData type must be the same for Without to work
     mut player_transform: Query<&mut Transform, With<Player>>,
     mut legs_transform: Query<&mut Transform, (With<PlayerLegs>, Without<Player>)>,
 ) -> Result {
-    let (camera, camera_transform) = camera.into_inner();
-    let cursor_world_position = match window.cursor_position() {
-        Some(viewport_position) => camera
-            .viewport_to_world_2d(camera_transform, viewport_position)
-            .expect("Viewport to world conversion should never fail"),
-        None => return Ok(()),
+    // Keep aiming at the last known cursor position when the cursor leaves the window
+    let Some(cursor_world_position) = mouse_cache.last_world_position() else {
+        return Ok(());
     };
 
     let mut player_transform = player_transform.single_mut()?;
@@ -249,11 +294,3 @@ fn rotate_player(
 
     Ok(())
 }
-
-// TODO: move this to a better place
-fn camera_tracking(
-    player: Single<&Transform, With<Player>>,
-    mut camera: Single<&mut Transform, (With<PlayerCamera>, Without<Player>)>,
-) {
-    camera.translation = player.translation;
-}
@@ -1,3 +1,5 @@
 mod door;
+mod key;
 
 pub use door::*;
+pub use key::*;
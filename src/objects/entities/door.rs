@@ -1,141 +1,924 @@
+mod automatic;
 mod door_shader;
+mod lock;
+mod snapshot;
 
-use derive::SectorMessage;
+pub use automatic::*;
 pub use door_shader::*;
+pub use lock::*;
+pub use snapshot::*;
 
-use bevy::{animation::AnimationTargetId, prelude::*};
+use avian2d::{collision::collider::contact_query, prelude::*};
+use bevy::{ecs::entity::EntityHashSet, prelude::*};
+use serde::{Deserialize, Serialize};
 
-use crate::{objects::characters::CharacterController, sector::SectorMessage};
+use crate::{
+    camera::CameraTrauma,
+    interaction::{Interactable, InteractionSystems, InteractionsWith},
+    objects::characters::{CharacterController, ControllerPush, Inventory, Player},
+    persistence::PersistenceAppExt,
+    physics::ObjectLayer,
+    sector::{TriggerDetectionSystems, TriggerPlugin},
+    signal::{SignalReceiver, SignalSystems, Signals},
+};
 
-#[derive(Component)]
-#[component(storage = "SparseSet")]
-pub struct DoorIsOpen;
+/// Camera trauma added when the player slams a door shut right next to them.
+const DOOR_SLAM_TRAUMA: f32 = 0.3;
 
+/// Distance from the player beyond which slamming doors don't shake the camera anymore.
+const DOOR_SLAM_RADIUS: f32 = 400.0;
+
+/// Seconds a door takes to fully open or close by default.
+const DEFAULT_DOOR_DURATION: f32 = 0.4;
+
+/// Only present while the door is fully open.
 #[derive(Component)]
 #[component(storage = "SparseSet")]
-pub struct DoorIsFocused;
+pub struct DoorIsOpen;
 
-pub struct DoorPlugin;
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DoorState {
+    #[default]
+    Closed,
+    Opening,
+    Open,
+    Closing,
+}
 
-impl Plugin for DoorPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_plugins(DoorShaderPlugin)
-            .init_resource::<DoorColors>()
-            .add_message::<DoorMessage>()
-            .add_systems(Update, update_doors)
-            .add_systems(FixedUpdate, read_door_focused_message);
+impl DoorState {
+    /// Interaction prompt of a door in this state.
+    fn prompt(self) -> &'static str {
+        match self {
+            DoorState::Closed | DoorState::Closing => "Open",
+            DoorState::Open | DoorState::Opening => "Close",
+        }
     }
 }
 
-#[derive(Message, SectorMessage)]
-pub struct DoorMessage {
-    entity: Entity,
+/// How a door moves from its closed to its open position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DoorMotion {
+    /// The door slides by `offset`, in world space.
+    Slide { offset: Vec2 },
+    /// The door rotates by `angle` radians around `pivot`, which is relative to the center of the
+    /// door in its local space. Positive angles open counterclockwise, unless the door swings
+    /// away from whoever opens it.
+    Hinge {
+        pivot: Vec2,
+        angle: f32,
+        swing_away: bool,
+    },
 }
 
-impl From<Entity> for DoorMessage {
-    fn from(value: Entity) -> Self {
-        Self { entity: value }
-    }
+/// What a closing door does when a character stands in its path.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DoorObstructionPolicy {
+    /// The door holds its position until the path is clear and then keeps closing.
+    Stop,
+    /// The door opens again.
+    #[default]
+    Reverse,
+    /// The door keeps closing and pushes the character out of its shape the shortest way.
+    Push,
 }
 
-fn read_door_focused_message(
-    mut doors: Query<Entity, With<Door>>,
-    mut door_messages: MessageReader<DoorMessage>,
-    mut commands: Commands,
-) {
-    let active_doors: Vec<Entity> = door_messages.read().map(|message| message.entity).collect();
-    for door_entity in &mut doors {
-        if active_doors.contains(&door_entity) {
-            commands.entity(door_entity).insert(DoorIsFocused);
-        } else {
-            commands.entity(door_entity).remove::<DoorIsFocused>();
-        }
+/// Sent when a closing door runs into a character, once per obstruction.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct DoorBlocked {
+    pub door: Entity,
+    /// Character standing in the path of the door.
+    pub blocker: Entity,
+    pub policy: DoorObstructionPolicy,
+}
+
+pub struct DoorPlugin;
+
+impl Plugin for DoorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            DoorShaderPlugin,
+            TriggerPlugin::<DoorSensorMessage>::default(),
+        ))
+        .init_resource::<DoorColors>()
+        .add_message::<DoorBlocked>()
+        .add_message::<DoorLocked>()
+        .add_message::<OperateDoor>()
+        .register_persistent::<Door>()
+        .add_systems(
+            Update,
+            (update_doors, operate_doors)
+                .chain()
+                .after(InteractionSystems),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                spawn_door_sensors.before(TriggerDetectionSystems),
+                update_automatic_doors.after(TriggerDetectionSystems),
+                open_doors_from_signals.after(SignalSystems),
+                animate_doors,
+            ),
+        );
     }
 }
 
-#[derive(Resource)]
+/// Sent when a door should start opening or closing.
+#[derive(Message)]
+struct OperateDoor {
+    door: Entity,
+    opener: Option<Entity>,
+    /// Whether the door should open or close, [`None`] toggles it.
+    open: Option<bool>,
+}
+
+/// Look of the doors without colors of their own, see [`Door::with_colors`].
+#[derive(Resource, Clone, Debug)]
 pub struct DoorColors {
+    /// Fill of a closed door, blended towards `open_color` while the door opens.
     pub fill_color: LinearRgba,
+    pub open_color: LinearRgba,
+    pub locked_color: LinearRgba,
+    /// Outline shown as the player gets close to the door.
+    pub rim_color: LinearRgba,
+    /// Outline of the focused door.
     pub focus_color: LinearRgba,
+    /// Width of the outline in world units.
+    pub rim_width: f32,
+    /// Distance from the player at which the outline starts to show.
+    pub highlight_radius: f32,
+    /// Pulses per second of the outline while the door is focused.
+    pub pulse_rate: f32,
 }
 
 impl Default for DoorColors {
     fn default() -> Self {
         Self {
             fill_color: LinearRgba::new(0.0, 0.0, 1.0, 1.0),
-            focus_color: LinearRgba::new(0.0, 0.25, 1.0, 1.0),
+            open_color: LinearRgba::new(0.0, 0.0, 0.5, 1.0),
+            locked_color: LinearRgba::new(0.6, 0.05, 0.05, 1.0),
+            rim_color: LinearRgba::new(0.3, 0.5, 1.0, 1.0),
+            focus_color: LinearRgba::new(0.6, 0.9, 1.0, 1.0),
+            rim_width: 4.0,
+            highlight_radius: 150.0,
+            pulse_rate: 1.5,
         }
     }
 }
 
 #[derive(Component)]
+#[require(
+    DoorState,
+    RigidBody = RigidBody::Kinematic,
+    Interactable = Interactable::new("Open")
+)]
 pub struct Door {
-    fill_color: LinearRgba,
-    focus_color: LinearRgba,
-    offset: Vec2,
+    /// Overrides the [`DoorColors`] resource for this door.
+    colors: Option<DoorColors>,
+    motion: DoorMotion,
+    /// Seconds the door takes to fully open or close.
+    duration: f32,
+    easing: EaseFunction,
+    /// How far the door is open, from `0.0` (closed) to `1.0` (open) before easing.
+    progress: f32,
+    obstruction_policy: DoorObstructionPolicy,
+    /// Whether a character was in the path of the door on the last update.
+    is_blocked: bool,
+    lock: Option<LockRequirement>,
+    is_unlocked: bool,
+    /// Pose of the fully closed door, taken when the door first starts moving.
+    closed_pose: Option<Isometry2d>,
+    /// Direction a hinged door swings in, `1.0` for the direction of its angle and `-1.0` for the
+    /// opposite one.
+    swing: f32,
+    /// Whether the player closed the door, which slams it once it is shut.
+    closed_by_player: bool,
 }
 
 impl Door {
-    pub fn new(fill_color: LinearRgba, focus_color: LinearRgba, offset: Vec2) -> Self {
+    pub fn new(motion: DoorMotion) -> Self {
+        Self {
+            colors: None,
+            motion,
+            duration: DEFAULT_DOOR_DURATION,
+            easing: EaseFunction::CubicInOut,
+            progress: 0.0,
+            obstruction_policy: DoorObstructionPolicy::default(),
+            is_blocked: false,
+            lock: None,
+            is_unlocked: false,
+            closed_pose: None,
+            swing: 1.0,
+            closed_by_player: false,
+        }
+    }
+
+    pub fn with_colors(self, colors: DoorColors) -> Self {
+        Self {
+            colors: Some(colors),
+            ..self
+        }
+    }
+
+    pub fn with_obstruction_policy(self, obstruction_policy: DoorObstructionPolicy) -> Self {
+        Self {
+            obstruction_policy,
+            ..self
+        }
+    }
+
+    pub fn with_lock(self, requirement: LockRequirement) -> Self {
+        Self {
+            lock: Some(requirement),
+            ..self
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.lock.is_some() && !self.is_unlocked
+    }
+
+    /// Unlocks the door for good, regardless of its requirement.
+    pub fn unlock(&mut self) {
+        self.is_unlocked = true;
+    }
+
+    pub fn with_animation(self, duration: f32, easing: EaseFunction) -> Self {
         Self {
-            fill_color,
-            focus_color,
-            offset,
-        }
-    }
-}
-
-// // Holds information about the animation we programmatically create.
-// pub struct AnimationInfo {
-//     // The name of the animation target (in this case, the text).
-//     target_name: Name,
-//     // The ID of the animation target, derived from the name.
-//     target_id: AnimationTargetId,
-//     // The animation graph asset.
-//     graph: Handle<AnimationGraph>,
-//     // The index of the node within that graph.
-//     node_index: AnimationNodeIndex,
-// }
-
-// impl AnimationInfo {
-//     fn create() -> AnimationInfo {
-//
-//     }
-// }
-//
-//
-// fn spawn_door() -> impl Bundle {
-//     let AnimationInfo
-// }
+            duration,
+            easing,
+            ..self
+        }
+    }
+
+    /// Pose of the door at the current progress.
+    fn pose(&self, closed_pose: Isometry2d) -> Isometry2d {
+        let eased = self.easing.sample_clamped(self.progress);
+
+        match self.motion {
+            DoorMotion::Slide { offset } => Isometry2d::new(
+                closed_pose.translation + offset * eased,
+                closed_pose.rotation,
+            ),
+            DoorMotion::Hinge { pivot, angle, .. } => {
+                let pivot = closed_pose.transform_point(pivot);
+                let rotation = Rot2::radians(angle * self.swing * eased);
+                Isometry2d::new(
+                    pivot + rotation * (closed_pose.translation - pivot),
+                    rotation * closed_pose.rotation,
+                )
+            }
+        }
+    }
+
+    /// Normal of the plane the door lies in when its rotation is `rotation`.
+    fn normal(&self, rotation: Rot2) -> Vec2 {
+        match self.motion {
+            // Doors slide along their length
+            DoorMotion::Slide { offset } => offset.perp().normalize_or_zero(),
+            DoorMotion::Hinge { pivot, .. } => (rotation * pivot.perp()).normalize_or_zero(),
+        }
+    }
+
+    /// Makes a closed hinged door that swings away from its opener open away from `opener`.
+    fn swing_away_from(&mut self, opener: Vec2, position: Vec2, rotation: Rot2) {
+        let DoorMotion::Hinge {
+            swing_away: true, ..
+        } = self.motion
+        else {
+            return;
+        };
 
+        if self.progress <= 0.0 {
+            // Opening counterclockwise moves the door towards the negative side of its normal
+            let side = (opener - position).dot(self.normal(rotation));
+            self.swing = if side < 0.0 { -1.0 } else { 1.0 };
+        }
+    }
+}
+
+/// Opens and closes doors that are interacted with, locked doors are only toggled once the
+/// interactor meets their [`LockRequirement`].
 fn update_doors(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    signals: Res<Signals>,
+    inventories: Query<&Inventory>,
     mut commands: Commands,
+    mut interactions: InteractionsWith<Door>,
+    mut doors: Query<&mut Door>,
+    mut door_messages: MessageWriter<OperateDoor>,
+    mut locked_messages: MessageWriter<DoorLocked>,
+) {
+    for interaction in interactions.read() {
+        let (door_entity_id, interactor) = (interaction.target, interaction.interactor);
+        let Ok(mut door) = doors.get_mut(door_entity_id) else {
+            continue;
+        };
+        let toggle = OperateDoor {
+            door: door_entity_id,
+            opener: Some(interactor),
+            open: None,
+        };
+
+        let Some(requirement) = door.lock.clone().filter(|_| !door.is_unlocked) else {
+            door_messages.write(toggle);
+            continue;
+        };
+
+        match requirement.is_met(inventories.get(interactor).ok(), &signals) {
+            Some(true) => {
+                door.unlock();
+                door_messages.write(toggle);
+            }
+            Some(false) => {
+                locked_messages.write(DoorLocked {
+                    door: door_entity_id,
+                    interactor,
+                    requirement,
+                });
+            }
+            None => {
+                let LockRequirement::Condition(condition) = requirement else {
+                    continue;
+                };
+
+                // Conditions can read anything, so they are run on the world after this system
+                commands.queue(move |world: &mut World| {
+                    let access = DoorAccess {
+                        door: door_entity_id,
+                        interactor,
+                    };
+                    if world.run_system_with(condition, access).unwrap_or(false) {
+                        if let Some(mut door) = world.get_mut::<Door>(door_entity_id) {
+                            door.unlock();
+                        }
+                        world.write_message(toggle);
+                    } else {
+                        world.write_message(DoorLocked {
+                            door: door_entity_id,
+                            interactor,
+                            requirement,
+                        });
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Opens doors with a [`SignalReceiver`] while their channel is on, locked doors stay closed.
+/// Doors already in the state their receiver asks for are left alone, so attaching a receiver
+/// keeps the state the door was restored in. Doors that stayed closed because they were locked
+/// open once they are unlocked while their channel is still on.
+#[allow(clippy::type_complexity)]
+fn open_doors_from_signals(
+    doors: Query<
+        (Entity, &Door, &DoorState, Ref<SignalReceiver>),
+        Or<(Changed<SignalReceiver>, Changed<Door>)>,
+    >,
+    mut locked_doors: Local<EntityHashSet>,
+    mut door_messages: MessageWriter<OperateDoor>,
+) {
+    for (entity, door, state, receiver) in &doors {
+        let was_unlocked = !door.is_locked() && locked_doors.remove(&entity);
+        if !receiver.is_changed() && !was_unlocked {
+            continue;
+        }
+
+        let is_open = matches!(state, DoorState::Opening | DoorState::Open);
+        if receiver.is_on() == is_open {
+            continue;
+        }
+        if receiver.is_on() && door.is_locked() {
+            locked_doors.insert(entity);
+            continue;
+        }
+
+        door_messages.write(OperateDoor {
+            door: entity,
+            opener: None,
+            open: Some(receiver.is_on()),
+        });
+    }
+}
+
+fn operate_doors(
+    openers: Query<&GlobalTransform>,
+    players: Query<(), With<Player>>,
+    mut commands: Commands,
+    mut door_messages: MessageReader<OperateDoor>,
+    mut doors: Query<(
+        &mut Door,
+        &mut DoorState,
+        &mut Interactable,
+        &Position,
+        &Rotation,
+    )>,
+) {
+    for message in door_messages.read() {
+        let Ok((mut door, mut state, mut interactable, position, rotation)) =
+            doors.get_mut(message.door)
+        else {
+            continue;
+        };
+
+        let open = message
+            .open
+            .unwrap_or(matches!(*state, DoorState::Closed | DoorState::Closing));
+
+        // Interrupted animations reverse from wherever the door currently is
+        *state = match (open, *state) {
+            (true, DoorState::Closed | DoorState::Closing) => {
+                if let Some(Ok(opener)) = message.opener.map(|opener| openers.get(opener)) {
+                    door.swing_away_from(
+                        opener.translation().xy(),
+                        position.0,
+                        Rot2::radians(rotation.as_radians()),
+                    );
+                }
+                DoorState::Opening
+            }
+            (false, DoorState::Open | DoorState::Opening) => {
+                door.closed_by_player = message
+                    .opener
+                    .is_some_and(|opener| players.contains(opener));
+                commands.entity(message.door).remove::<DoorIsOpen>();
+                DoorState::Closing
+            }
+            (_, state) => state,
+        };
+
+        interactable.prompt = state.prompt().to_owned();
+    }
+}
+
+/// Moves opening and closing doors along their path. The kinematic body is moved through its
+/// velocities so that the physics step sweeps it along the path instead of teleporting it.
+///
+/// Closing doors check the movement of the current tick for characters first, and follow their
+/// [`DoorObstructionPolicy`] if one is in the way. Hinged doors also wait for characters in their
+/// way while opening, and stay open wherever they run into a wall. Doors the player closed shake
+/// the camera once they are shut, less the further away the player is.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn animate_doors(
+    time: Res<Time>,
+    spatial_query: Res<SpatialQueryPipeline>,
+    mut commands: Commands,
+    mut camera_trauma: MessageWriter<CameraTrauma>,
+    mut blocked_messages: MessageWriter<DoorBlocked>,
     mut doors: Query<
         (
-            &mut Transform,
-            &Door,
             Entity,
-            Has<DoorIsFocused>,
-            Has<DoorIsOpen>,
+            &mut Door,
+            &mut DoorState,
+            &mut Interactable,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            &Collider,
+            &Position,
+            &Rotation,
         ),
         Without<CharacterController>,
     >,
+    mut push_messages: MessageWriter<ControllerPush>,
+    characters: Query<(&Collider, &Position, &Rotation), With<CharacterController>>,
+    players: Query<&GlobalTransform, With<Player>>,
 ) {
-    for (mut door_transform, door, door_entity_id, is_focused, is_open) in &mut doors {
-        if !is_focused {
+    let delta = time.delta_secs();
+    if delta <= 0.0 {
+        return;
+    }
+
+    let walls = SpatialQueryFilter::from_mask(LayerMask(ObjectLayer::Obstacle.to_bits()));
+
+    for (
+        entity,
+        mut door,
+        mut state,
+        mut interactable,
+        mut velocity,
+        mut angular_velocity,
+        collider,
+        position,
+        rotation,
+    ) in &mut doors
+    {
+        let direction = match *state {
+            DoorState::Opening => 1.0,
+            DoorState::Closing => -1.0,
+            DoorState::Open | DoorState::Closed => {
+                velocity.set_if_neq(LinearVelocity::ZERO);
+                angular_velocity.set_if_neq(AngularVelocity::ZERO);
+                continue;
+            }
+        };
+
+        let rotation = Rot2::radians(rotation.as_radians());
+        let closed_pose = *door
+            .closed_pose
+            .get_or_insert(Isometry2d::new(position.0, rotation));
+
+        let previous_progress = door.progress;
+        door.progress = (previous_progress + direction * delta / door.duration.max(f32::EPSILON))
+            .clamp(0.0, 1.0);
+        let target = door.pose(closed_pose);
+        let movement = target.translation - position.0;
+
+        let others = SpatialQueryFilter::default().with_excluded_entities([entity]);
+        let blocker = match door.motion {
+            DoorMotion::Slide { .. } => match (*state, Dir2::new(movement)) {
+                (DoorState::Closing, Ok(movement_direction)) => spatial_query
+                    .cast_shape_predicate(
+                        collider,
+                        position.0,
+                        rotation.as_radians(),
+                        movement_direction,
+                        &ShapeCastConfig {
+                            max_distance: movement.length(),
+                            ..default()
+                        },
+                        &others,
+                        &|hit_entity| characters.contains(hit_entity),
+                    )
+                    .map(|hit| hit.entity),
+                _ => None,
+            },
+            // The rotation of a single tick is small enough to only check the pose it ends in
+            DoorMotion::Hinge { .. } => spatial_query
+                .shape_intersections(
+                    collider,
+                    target.translation,
+                    target.rotation.as_radians(),
+                    &others,
+                )
+                .into_iter()
+                .find(|hit_entity| characters.contains(*hit_entity)),
+        };
+
+        let hits_wall =
+            matches!(door.motion, DoorMotion::Hinge { .. }) && *state == DoorState::Opening && {
+                // Walls the door already rests against, like the one at its hinge, don't block it
+                let resting = spatial_query.shape_intersections(
+                    collider,
+                    position.0,
+                    rotation.as_radians(),
+                    &walls,
+                );
+                spatial_query
+                    .shape_intersections(
+                        collider,
+                        target.translation,
+                        target.rotation.as_radians(),
+                        &walls,
+                    )
+                    .iter()
+                    .any(|wall| !resting.contains(wall))
+            };
+
+        if hits_wall || (blocker.is_some() && *state == DoorState::Opening) {
+            door.progress = previous_progress;
+            velocity.0 = Vec2::ZERO;
+            angular_velocity.0 = 0.0;
+
+            if hits_wall {
+                *state = DoorState::Open;
+                commands.entity(entity).insert(DoorIsOpen);
+            }
             continue;
         }
 
-        let mut entity = commands.entity(door_entity_id);
-        if keyboard.just_pressed(KeyCode::Space) {
-            if is_open {
-                entity.remove::<DoorIsOpen>();
-                door_transform.translation -= door.offset.extend(0.0);
-            } else {
-                entity.insert(DoorIsOpen);
-                door_transform.translation += door.offset.extend(0.0);
+        let Some(blocker) = blocker else {
+            door.is_blocked = false;
+            velocity.0 = movement / delta;
+            angular_velocity.0 = rotation.angle_to(target.rotation) / delta;
+
+            if door.progress >= 1.0 {
+                *state = DoorState::Open;
+                commands.entity(entity).insert(DoorIsOpen);
+            } else if door.progress <= 0.0 {
+                *state = DoorState::Closed;
+
+                if door.closed_by_player
+                    && let Ok(player) = players.single()
+                {
+                    let trauma = slam_trauma(position.distance(player.translation().xy()));
+                    if trauma > 0.0 {
+                        camera_trauma.write(CameraTrauma(trauma));
+                    }
+                }
             }
+            continue;
+        };
+
+        if !door.is_blocked {
+            blocked_messages.write(DoorBlocked {
+                door: entity,
+                blocker,
+                policy: door.obstruction_policy,
+            });
         }
+        door.is_blocked = true;
+
+        match door.obstruction_policy {
+            DoorObstructionPolicy::Stop => {
+                door.progress = previous_progress;
+                velocity.0 = Vec2::ZERO;
+                angular_velocity.0 = 0.0;
+            }
+            DoorObstructionPolicy::Reverse => {
+                door.progress = previous_progress;
+                velocity.0 = Vec2::ZERO;
+                angular_velocity.0 = 0.0;
+                *state = DoorState::Opening;
+                interactable.prompt = state.prompt().to_owned();
+            }
+            DoorObstructionPolicy::Push => {
+                velocity.0 = movement / delta;
+                angular_velocity.0 = rotation.angle_to(target.rotation) / delta;
+
+                let Ok((character_collider, character_position, character_rotation)) =
+                    characters.get(blocker)
+                else {
+                    continue;
+                };
+
+                // Overlap with the door in the pose it ends this tick in, which the controller
+                // resolves before the character moves on the next tick
+                let target_rotation = Rotation::radians(target.rotation.as_radians());
+                let contact = contact_query::contact(
+                    collider,
+                    Position(target.translation),
+                    target_rotation,
+                    character_collider,
+                    *character_position,
+                    *character_rotation,
+                    0.0,
+                );
+                if let Ok(Some(contact)) = contact
+                    && contact.penetration > 0.0
+                {
+                    push_messages.write(ControllerPush {
+                        entity: blocker,
+                        source: entity,
+                        displacement: contact.global_normal1(&target_rotation)
+                            * contact.penetration,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Camera trauma of a door slamming shut `distance` world units away from the player, which fades
+/// out towards [`DOOR_SLAM_RADIUS`].
+fn slam_trauma(distance: f32) -> f32 {
+    DOOR_SLAM_TRAUMA * (1.0 - distance / DOOR_SLAM_RADIUS).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f32::consts::PI, time::Duration};
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use crate::signal::{
+        SignalEmitter, SignalTimer, collect_signals, update_receivers, update_signal_timers,
+    };
+
+    use super::*;
+
+    fn signal_world() -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Signals>();
+        world.init_resource::<Messages<OperateDoor>>();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                update_signal_timers,
+                collect_signals,
+                update_receivers,
+                open_doors_from_signals,
+            )
+                .chain(),
+        );
+        (world, schedule)
+    }
+
+    fn door_messages(world: &mut World) -> Vec<Option<bool>> {
+        world
+            .resource_mut::<Messages<OperateDoor>>()
+            .drain()
+            .map(|message| message.open)
+            .collect()
+    }
+
+    #[test]
+    fn doors_keep_their_state_while_it_matches_their_receiver() {
+        let (mut world, mut schedule) = signal_world();
+        let door = world
+            .spawn((
+                Door::new(DoorMotion::Slide { offset: Vec2::X }),
+                SignalReceiver::new("door"),
+            ))
+            .id();
+
+        schedule.run(&mut world);
+        assert_eq!(door_messages(&mut world), []);
+
+        // A door restored as open stays open once its channel turns on
+        world.entity_mut(door).insert(DoorState::Open);
+        world.spawn((SignalTimer::new(2.0, 1.0), SignalEmitter::new("door")));
+        schedule.run(&mut world);
+        assert_eq!(door_messages(&mut world), []);
+    }
+
+    #[test]
+    fn doors_open_from_emitters_that_are_on_at_spawn() {
+        let (mut world, mut schedule) = signal_world();
+        world.spawn((SignalTimer::new(2.0, 1.0), SignalEmitter::new("door")));
+        world.spawn((
+            Door::new(DoorMotion::Slide { offset: Vec2::X }),
+            SignalReceiver::new("door"),
+        ));
+
+        // The timer turns on in the tick the receiver is attached
+        schedule.run(&mut world);
+        assert_eq!(door_messages(&mut world), [Some(true)]);
+    }
+
+    #[test]
+    fn locked_doors_open_once_unlocked_while_their_channel_is_on() {
+        let (mut world, mut schedule) = signal_world();
+        let door = world
+            .spawn((
+                Door::new(DoorMotion::Slide { offset: Vec2::X })
+                    .with_lock(LockRequirement::Key("key".to_owned())),
+                SignalReceiver::new("door"),
+            ))
+            .id();
+        schedule.run(&mut world);
+
+        world.spawn((SignalTimer::new(2.0, 1.0), SignalEmitter::new("door")));
+        schedule.run(&mut world);
+        assert_eq!(door_messages(&mut world), []);
+
+        world.get_mut::<Door>(door).unwrap().unlock();
+        schedule.run(&mut world);
+        assert_eq!(door_messages(&mut world), [Some(true)]);
+
+        // Later changes of the door don't reopen it
+        world.entity_mut(door).insert(DoorState::Closing);
+        world.get_mut::<Door>(door).unwrap().progress = 0.5;
+        schedule.run(&mut world);
+        assert_eq!(door_messages(&mut world), []);
+    }
+
+    /// World for running [`animate_doors`] a tenth of a second at a time.
+    fn animate_world() -> World {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs_f32(0.1));
+        world.insert_resource(time);
+        world.init_resource::<Messages<CameraTrauma>>();
+        world.init_resource::<Messages<DoorBlocked>>();
+        world.init_resource::<Messages<ControllerPush>>();
+        world
+    }
+
+    #[test]
+    fn reversed_doors_prompt_to_close_again() {
+        let mut world = animate_world();
+
+        // Door halfway closed from `(100, 0)` to `(0, 0)`, with a character in its path
+        let mut door = Door::new(DoorMotion::Slide {
+            offset: vec2(100.0, 0.0),
+        });
+        door.progress = 0.5;
+        door.closed_pose = Some(Isometry2d::IDENTITY);
+        let door = world
+            .spawn((
+                door,
+                DoorState::Closing,
+                Interactable::new("Open"),
+                Collider::rectangle(10.0, 40.0),
+                Position(vec2(50.0, 0.0)),
+                Rotation::default(),
+            ))
+            .id();
+        world.spawn((
+            CharacterController,
+            Collider::circle(10.0),
+            Position(vec2(30.0, 0.0)),
+            Rotation::default(),
+        ));
+
+        let mut colliders =
+            world.query::<(Entity, &Position, &Rotation, &Collider, &CollisionLayers)>();
+        let mut spatial_query = SpatialQueryPipeline::new();
+        spatial_query.update(colliders.iter(&world));
+        world.insert_resource(spatial_query);
+
+        world.run_system_once(animate_doors).unwrap();
+
+        assert_eq!(world.get::<DoorState>(door), Some(&DoorState::Opening));
+        assert_eq!(world.get::<Interactable>(door).unwrap().prompt, "Close");
+    }
+
+    #[test]
+    fn only_doors_closed_by_the_player_shake_the_camera() {
+        let mut world = animate_world();
+        world.init_resource::<SpatialQueryPipeline>();
+        world.spawn((Player::default(), GlobalTransform::default()));
+
+        // Both doors shut during the next update
+        for (position, closed_by_player) in [(vec2(100.0, 0.0), true), (vec2(0.0, 50.0), false)] {
+            let mut door = Door::new(DoorMotion::Slide {
+                offset: vec2(0.0, 100.0),
+            });
+            door.progress = 0.1;
+            door.closed_pose = Some(Isometry2d::from_translation(position));
+            door.closed_by_player = closed_by_player;
+            world.spawn((
+                door,
+                DoorState::Closing,
+                Collider::rectangle(10.0, 40.0),
+                Position(position),
+                Rotation::default(),
+            ));
+        }
+
+        world.run_system_once(animate_doors).unwrap();
+
+        let trauma: Vec<f32> = world
+            .resource_mut::<Messages<CameraTrauma>>()
+            .drain()
+            .map(|CameraTrauma(trauma)| trauma)
+            .collect();
+        assert_eq!(trauma, [slam_trauma(100.0)]);
+    }
+
+    #[test]
+    fn slam_trauma_fades_out_with_the_distance_to_the_player() {
+        assert_eq!(slam_trauma(0.0), DOOR_SLAM_TRAUMA);
+        assert!(slam_trauma(DOOR_SLAM_RADIUS / 2.0) < DOOR_SLAM_TRAUMA);
+        assert_eq!(slam_trauma(DOOR_SLAM_RADIUS), 0.0);
+        assert_eq!(slam_trauma(DOOR_SLAM_RADIUS * 2.0), 0.0);
+    }
+
+    fn hinged_door(swing_away: bool) -> Door {
+        Door::new(DoorMotion::Hinge {
+            pivot: vec2(-50.0, 0.0),
+            angle: PI / 2.0,
+            swing_away,
+        })
+    }
+
+    /// Whether the door opened by `opener` ends up on the other side of where it was closed.
+    fn swings_away(mut door: Door, closed_pose: Isometry2d, opener: Vec2) -> bool {
+        door.swing_away_from(opener, closed_pose.translation, closed_pose.rotation);
+        door.progress = 1.0;
+        let movement = door.pose(closed_pose).translation - closed_pose.translation;
+        movement.dot(opener - closed_pose.translation) < 0.0
+    }
+
+    #[test]
+    fn hinged_doors_swing_away_from_openers_on_either_side() {
+        for closed_pose in [
+            Isometry2d::IDENTITY,
+            Isometry2d::new(vec2(100.0, 0.0), Rot2::degrees(90.0)),
+        ] {
+            for side in [1.0, -1.0] {
+                let opener = closed_pose.transform_point(vec2(0.0, 30.0 * side));
+                assert!(
+                    swings_away(hinged_door(true), closed_pose, opener),
+                    "{closed_pose:?} {opener}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hinged_doors_without_swing_away_open_towards_their_angle() {
+        let closed_pose = Isometry2d::IDENTITY;
+        assert!(!swings_away(
+            hinged_door(false),
+            closed_pose,
+            vec2(0.0, 30.0)
+        ));
+        assert!(swings_away(
+            hinged_door(false),
+            closed_pose,
+            vec2(0.0, -30.0)
+        ));
+    }
+
+    #[test]
+    fn hinged_doors_keep_their_swing_until_they_are_closed() {
+        let mut door = hinged_door(true);
+        door.swing_away_from(vec2(0.0, 30.0), Vec2::ZERO, Rot2::IDENTITY);
+        door.progress = 0.5;
+
+        // Opened again from the other side while still half open
+        assert!(!swings_away(door, Isometry2d::IDENTITY, vec2(0.0, -30.0)));
     }
 }
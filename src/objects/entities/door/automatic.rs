@@ -0,0 +1,263 @@
+use avian2d::prelude::*;
+use bevy::{ecs::entity::EntityHashSet, prelude::*};
+use derive::TriggerMessage;
+
+use crate::sector::{
+    AnnulusShape, RectangleShape, ShapeTrigger, TriggerChannel, TriggerTransition,
+};
+
+use super::{Door, DoorState, OperateDoor};
+
+/// Zone in front of and behind an automatic door, centered on the closed door.
+#[derive(Clone, Copy, Debug)]
+pub enum DoorSensorZone {
+    /// Rectangle in the local space of the door.
+    Rectangle {
+        half_size: Vec2,
+    },
+    Circle {
+        radius: f32,
+    },
+}
+
+/// Opens the door when anything on `mask` enters its sensor zone, and closes it `close_delay`
+/// seconds after the zone is empty again. Locked doors stay closed until they are unlocked by
+/// hand.
+#[derive(Component)]
+pub struct AutomaticDoor {
+    pub zone: DoorSensorZone,
+    pub mask: LayerMask,
+    pub close_delay: f32,
+    occupants: EntityHashSet,
+    /// Seconds since the last occupant left the zone.
+    time_empty: f32,
+}
+
+impl AutomaticDoor {
+    pub fn new(zone: DoorSensorZone, mask: LayerMask) -> Self {
+        Self {
+            zone,
+            mask,
+            close_delay: 1.0,
+            occupants: EntityHashSet::default(),
+            time_empty: 0.0,
+        }
+    }
+
+    pub fn with_close_delay(self, close_delay: f32) -> Self {
+        Self {
+            close_delay,
+            ..self
+        }
+    }
+
+    /// Entities currently inside the sensor zone.
+    pub fn occupants(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.occupants.iter().copied()
+    }
+}
+
+/// Sensor zone of an [`AutomaticDoor`]. Kept apart from the door so it doesn't move along with it,
+/// but despawned along with it through [`AutomaticDoorSensor`].
+#[derive(Component)]
+#[relationship(relationship_target = AutomaticDoorSensor)]
+pub struct DoorSensor {
+    #[relationship]
+    pub door: Entity,
+}
+
+/// Sensor entity of an [`AutomaticDoor`], see [`DoorSensor`].
+#[derive(Component)]
+#[relationship_target(relationship = DoorSensor, linked_spawn)]
+pub struct AutomaticDoorSensor(Entity);
+
+impl AutomaticDoorSensor {
+    pub fn sensor(&self) -> Entity {
+        self.0
+    }
+}
+
+#[derive(Message, TriggerMessage)]
+pub struct DoorSensorMessage {
+    transition: TriggerTransition,
+    trigger: Entity,
+    target: Entity,
+}
+
+pub(super) fn spawn_door_sensors(
+    doors: Query<(Entity, &AutomaticDoor, &Transform), Added<AutomaticDoor>>,
+    mut commands: Commands,
+) {
+    for (door, automatic, transform) in &doors {
+        let trigger = ShapeTrigger::new().with_channel(
+            TriggerChannel::new::<DoorSensorMessage>(automatic.mask).with_stay_messages(false),
+        );
+        let sensor = (DoorSensor { door }, trigger, *transform);
+
+        match automatic.zone {
+            DoorSensorZone::Rectangle { half_size } => {
+                commands.spawn((sensor, RectangleShape::new(half_size)));
+            }
+            DoorSensorZone::Circle { radius } => {
+                commands.spawn((sensor, AnnulusShape::new(0.0, radius, 8.0)));
+            }
+        }
+    }
+}
+
+pub(super) fn update_automatic_doors(
+    time: Res<Time>,
+    sensors: Query<&DoorSensor>,
+    mut sensor_messages: MessageReader<DoorSensorMessage>,
+    mut doors: Query<(Entity, &Door, &DoorState, &mut AutomaticDoor)>,
+    mut door_messages: MessageWriter<OperateDoor>,
+) {
+    for message in sensor_messages.read() {
+        let Ok(sensor) = sensors.get(message.trigger) else {
+            continue;
+        };
+        let Ok((_, _, _, mut automatic)) = doors.get_mut(sensor.door) else {
+            continue;
+        };
+
+        match message.transition {
+            TriggerTransition::Enter => {
+                automatic.occupants.insert(message.target);
+            }
+            TriggerTransition::Exit => {
+                automatic.occupants.remove(&message.target);
+            }
+            TriggerTransition::Stay => {}
+        }
+    }
+
+    for (entity, door, state, mut automatic) in &mut doors {
+        let is_opening = matches!(state, DoorState::Opening | DoorState::Open);
+
+        let occupant = automatic.occupants().next();
+        if let Some(occupant) = occupant {
+            automatic.time_empty = 0.0;
+
+            if !is_opening && !door.is_locked() {
+                door_messages.write(OperateDoor {
+                    door: entity,
+                    opener: Some(occupant),
+                    open: Some(true),
+                });
+            }
+        } else {
+            automatic.time_empty += time.delta_secs();
+
+            if is_opening && automatic.time_empty >= automatic.close_delay {
+                door_messages.write(OperateDoor {
+                    door: entity,
+                    opener: None,
+                    open: Some(false),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{scene::ScenePlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::{
+        camera::CameraShakePlugin,
+        interaction::InteractionPlugin,
+        objects::{
+            characters::ControllerPush,
+            entities::{DoorMotion, DoorPlugin},
+        },
+        persistence::PersistencePlugin,
+        physics::ObjectLayer,
+        signal::SignalPlugin,
+    };
+
+    const TIMESTEP: Duration = Duration::from_millis(16);
+
+    #[test]
+    fn automatic_doors_open_for_characters_in_their_sensor_zone() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            // Collider constructors of avian depend on scenes
+            ScenePlugin,
+            PhysicsPlugins::default(),
+            PersistencePlugin,
+            InteractionPlugin,
+            SignalPlugin,
+            CameraShakePlugin,
+            DoorPlugin,
+        ))
+        .init_asset::<Mesh>()
+        // Used by the signal wiring
+        .init_asset::<ColorMaterial>()
+        // Written by doors pushing characters, registered by the character controller
+        .add_message::<ControllerPush>()
+        // Exactly one fixed tick per update
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TIMESTEP))
+        .insert_resource(Time::<Fixed>::from_duration(TIMESTEP));
+
+        app.finish();
+        app.cleanup();
+
+        let door = app
+            .world_mut()
+            .spawn((
+                Door::new(DoorMotion::Slide {
+                    offset: vec2(0.0, 100.0),
+                }),
+                AutomaticDoor::new(
+                    DoorSensorZone::Rectangle {
+                        half_size: vec2(60.0, 60.0),
+                    },
+                    LayerMask(ObjectLayer::Player.to_bits()),
+                ),
+                Collider::rectangle(10.0, 100.0),
+                CollisionLayers::new(
+                    LayerMask(ObjectLayer::Door.to_bits()),
+                    LayerMask(ObjectLayer::None.to_bits()),
+                ),
+                Transform::default(),
+            ))
+            .id();
+        app.world_mut().spawn((
+            Collider::circle(10.0),
+            CollisionLayers::new(LayerMask(ObjectLayer::Player.to_bits()), LayerMask::ALL),
+            Transform::from_xyz(40.0, 0.0, 0.0),
+        ));
+
+        // The sensor is spawned, queried and its messages read over the first few ticks
+        for _ in 0..4 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world().get::<DoorState>(door),
+            Some(&DoorState::Opening)
+        );
+    }
+
+    #[test]
+    fn sensors_are_despawned_along_with_their_door() {
+        let mut world = World::new();
+        let door = world.spawn_empty().id();
+        let sensor = world.spawn(DoorSensor { door }).id();
+
+        assert_eq!(
+            world.get::<AutomaticDoorSensor>(door).unwrap().sensor(),
+            sensor
+        );
+
+        world.despawn(door);
+
+        assert!(world.get_entity(sensor).is_err());
+    }
+}
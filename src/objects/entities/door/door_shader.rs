@@ -1,12 +1,21 @@
 use bevy::{
+    ecs::entity::EntityHashSet,
     prelude::*,
-    render::render_resource::AsBindGroup,
+    render::{
+        render_resource::{
+            AsBindGroup, Buffer, BufferDescriptor, BufferUsages, ShaderType, encase::StorageBuffer,
+        },
+        renderer::{RenderDevice, RenderQueue},
+    },
     sprite_render::{Material2d, Material2dPlugin},
 };
 
-use crate::objects::{
-    characters::Player,
-    entities::{Door, DoorIsFocused},
+use crate::{
+    interaction::Focused,
+    objects::{
+        characters::Player,
+        entities::{Door, DoorColors, DoorState},
+    },
 };
 
 const DOOR_SHADER_PATH: &str = "shaders/door.wgsl";
@@ -16,46 +25,154 @@ pub struct DoorShaderPlugin;
 impl Plugin for DoorShaderPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<DoorShader>::default())
-            .add_systems(Update, update_door_shaders);
+            .add_systems(
+                Update,
+                (
+                    update_door_player_position.run_if(resource_exists::<DoorPlayerPosition>),
+                    update_door_shaders,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        // The render device is only available once the renderer is initialized
+        if let Some(render_device) = app.world().get_resource::<RenderDevice>() {
+            let player_position = DoorPlayerPosition::new(render_device);
+            app.insert_resource(player_position);
+        }
     }
 }
 
+/// Buffer with the player position that is bound by every [`DoorShader`], so that the player
+/// moving only writes to this buffer instead of to the material of every door. Only present when
+/// there is a renderer, headless apps have no door materials.
+#[derive(Resource, Clone, Deref)]
+pub struct DoorPlayerPosition(Buffer);
+
+impl DoorPlayerPosition {
+    fn new(render_device: &RenderDevice) -> Self {
+        Self(render_device.create_buffer(&BufferDescriptor {
+            label: Some("door_player_position"),
+            size: Vec2::min_size().get(),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }))
+    }
+}
+
+fn update_door_player_position(
+    player: Single<Ref<Transform>, With<Player>>,
+    player_position: Res<DoorPlayerPosition>,
+    render_queue: Res<RenderQueue>,
+) {
+    if !player.is_changed() {
+        return;
+    }
+
+    let mut bytes = StorageBuffer::new(Vec::new());
+    if bytes.write(&player.translation.xy()).is_ok() {
+        render_queue.write_buffer(&player_position, 0, bytes.as_ref());
+    }
+}
+
+/// Syncs the material of the doors whose state, lock or focus changed. Every material is synced
+/// when the [`DoorColors`] change.
+#[allow(clippy::type_complexity)]
 fn update_door_shaders(
-    doors: Query<(&Door, Has<DoorIsFocused>)>,
-    player: Single<&Transform, With<Player>>,
-    mut door_highlight_shaders: ResMut<Assets<DoorShader>>,
+    door_colors: Res<DoorColors>,
+    changed_doors: Query<
+        Entity,
+        (
+            With<Door>,
+            Or<(
+                Changed<Door>,
+                Changed<DoorState>,
+                Added<Focused>,
+                Changed<MeshMaterial2d<DoorShader>>,
+            )>,
+        ),
+    >,
+    mut unfocused_doors: RemovedComponents<Focused>,
+    doors: Query<(Entity, &Door, &MeshMaterial2d<DoorShader>, Has<Focused>)>,
+    mut door_shaders: ResMut<Assets<DoorShader>>,
 ) {
-    for (_, shader) in door_highlight_shaders.iter_mut() {
-        let (door, is_focused) = doors
-            .get(shader.door_entity)
-            .expect("Door shaders are always spawned with a corresponding door object.");
+    let changed: EntityHashSet = if door_colors.is_changed() {
+        doors.iter().map(|(entity, ..)| entity).collect()
+    } else {
+        changed_doors.iter().chain(unfocused_doors.read()).collect()
+    };
+
+    for (_, door, material, is_focused) in doors.iter_many(&changed) {
+        let colors = door.colors.as_ref().unwrap_or(&door_colors);
 
-        shader.fill_color = if is_focused {
-            door.focus_color
+        let fill_color = if door.is_locked() {
+            colors.locked_color
         } else {
-            door.fill_color
+            colors.fill_color.mix(
+                &colors.open_color,
+                door.easing.sample_clamped(door.progress),
+            )
+        };
+
+        let rim = DoorRim {
+            color: if is_focused {
+                colors.focus_color
+            } else {
+                colors.rim_color
+            },
+            width: colors.rim_width,
+            highlight_radius: colors.highlight_radius,
+            focus: if is_focused { 1.0 } else { 0.0 },
+            pulse_rate: colors.pulse_rate,
         };
 
-        shader.player_position = player.translation.xy();
+        // Changes of the door that don't affect its look leave the material alone
+        if door_shaders
+            .get(&material.0)
+            .is_none_or(|shader| shader.fill_color == fill_color && shader.rim == rim)
+        {
+            continue;
+        }
+
+        if let Some(shader) = door_shaders.get_mut(&material.0) {
+            shader.fill_color = fill_color;
+            shader.rim = rim;
+        }
     }
 }
 
+/// Outline of a door, brighter the closer the player is and pulsing while the door is focused.
+#[derive(ShaderType, Clone, Debug, Default, PartialEq)]
+struct DoorRim {
+    color: LinearRgba,
+    /// World units.
+    width: f32,
+    highlight_radius: f32,
+    /// `1.0` while the door is focused and `0.0` otherwise.
+    focus: f32,
+    pulse_rate: f32,
+}
+
 #[derive(Asset, TypePath, AsBindGroup, Clone)]
 pub struct DoorShader {
     #[uniform(0)]
     fill_color: LinearRgba,
-    #[uniform(1)]
-    player_position: Vec2,
-    door_entity: Entity,
+    #[storage(1, read_only, buffer)]
+    player_position: Buffer,
+    #[uniform(2)]
+    rim: DoorRim,
+    /// Size of the door mesh in world units.
+    #[uniform(3)]
+    size: Vec2,
 }
 
 impl DoorShader {
-    pub fn new(fill_color: LinearRgba, entity: Entity) -> Self {
+    pub fn new(size: Vec2, player_position: &DoorPlayerPosition) -> Self {
         Self {
-            // Blue, full opacity
-            fill_color,
-            player_position: Vec2::ZERO,
-            door_entity: entity,
+            fill_color: LinearRgba::BLACK,
+            player_position: player_position.0.clone(),
+            rim: DoorRim::default(),
+            size,
         }
     }
 }
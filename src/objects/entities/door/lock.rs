@@ -0,0 +1,96 @@
+use bevy::{ecs::system::SystemId, prelude::*};
+
+use crate::{objects::characters::Inventory, signal::Signals};
+
+/// Door and character involved in an attempt to open a locked door.
+#[derive(Clone, Copy, Debug)]
+pub struct DoorAccess {
+    pub door: Entity,
+    pub interactor: Entity,
+}
+
+/// What it takes to unlock a door. Doors stay unlocked once the requirement has been met.
+#[derive(Clone, Debug)]
+pub enum LockRequirement {
+    /// The interactor carries the key with this id in its [`Inventory`].
+    Key(String),
+    /// The signal channel of a switch is in the given state.
+    Switch { channel: String, on: bool },
+    /// The registered system returns `true`.
+    Condition(SystemId<In<DoorAccess>, bool>),
+}
+
+impl LockRequirement {
+    pub fn switch(channel: impl Into<String>, on: bool) -> Self {
+        Self::Switch {
+            channel: channel.into(),
+            on,
+        }
+    }
+
+    /// Whether the requirement is met, or [`None`] for conditions that need to be run on the
+    /// world.
+    pub(super) fn is_met(&self, inventory: Option<&Inventory>, signals: &Signals) -> Option<bool> {
+        match self {
+            Self::Key(key) => Some(inventory.is_some_and(|inventory| inventory.has_key(key))),
+            Self::Switch { channel, on } => Some(signals.is_on(channel) == *on),
+            Self::Condition(_) => None,
+        }
+    }
+}
+
+/// Sent when a character tries to open a locked door without meeting its requirement.
+#[derive(Message, Clone, Debug)]
+pub struct DoorLocked {
+    pub door: Entity,
+    pub interactor: Entity,
+    pub requirement: LockRequirement,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_requirements_need_the_key_in_the_inventory() {
+        let requirement = LockRequirement::Key("north_key".into());
+        let signals = Signals::default();
+
+        let with_key = Inventory::default().with_key("north_key");
+        let other_key = Inventory::default().with_key("south_key");
+
+        assert_eq!(requirement.is_met(Some(&with_key), &signals), Some(true));
+        assert_eq!(requirement.is_met(Some(&other_key), &signals), Some(false));
+        assert_eq!(requirement.is_met(None, &signals), Some(false));
+    }
+
+    #[test]
+    fn switch_requirements_need_the_channel_in_the_given_state() {
+        let inventory = Inventory::default();
+        let on = Signals::from_iter([("gate", true)]);
+        let off = Signals::from_iter([("gate", false)]);
+
+        let needs_on = LockRequirement::switch("gate", true);
+        let needs_off = LockRequirement::switch("gate", false);
+
+        assert_eq!(needs_on.is_met(Some(&inventory), &on), Some(true));
+        assert_eq!(needs_on.is_met(Some(&inventory), &off), Some(false));
+        assert_eq!(needs_off.is_met(Some(&inventory), &off), Some(true));
+        assert_eq!(needs_off.is_met(Some(&inventory), &on), Some(false));
+        // Channels without emitters are off
+        assert_eq!(
+            needs_off.is_met(Some(&inventory), &Signals::default()),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn condition_requirements_have_to_be_run_on_the_world() {
+        let mut world = World::new();
+        let condition = world.register_system(|_: In<DoorAccess>| true);
+
+        let requirement = LockRequirement::Condition(condition);
+
+        assert_eq!(requirement.is_met(None, &Signals::default()), None);
+    }
+}
@@ -0,0 +1,201 @@
+use avian2d::prelude::*;
+use bevy::{
+    ecs::world::{EntityRef, EntityWorldMut},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{interaction::Interactable, persistence::Persistent};
+
+use super::{Door, DoorIsOpen, DoorState};
+
+/// Saved state of a [`Door`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DoorSnapshot {
+    pub state: DoorState,
+    pub progress: f32,
+    pub swing: f32,
+    /// Only restored on doors with a lock.
+    pub locked: bool,
+}
+
+impl Persistent for Door {
+    const KEY: &'static str = "door";
+
+    type State = DoorSnapshot;
+
+    fn save(entity: EntityRef) -> Option<Self::State> {
+        let door = entity.get::<Door>()?;
+
+        Some(DoorSnapshot {
+            state: *entity.get::<DoorState>()?,
+            progress: door.progress,
+            swing: door.swing,
+            locked: door.is_locked(),
+        })
+    }
+
+    /// Moves the door straight to the saved pose, doors that were moving keep moving from there.
+    fn restore(mut entity: EntityWorldMut, snapshot: Self::State) {
+        let Some(transform) = entity.get::<Transform>().copied() else {
+            return;
+        };
+
+        let Some(mut door) = entity.get_mut::<Door>() else {
+            return;
+        };
+        door.progress = snapshot.progress.clamp(0.0, 1.0);
+        door.swing = snapshot.swing;
+        if door.lock.is_some() {
+            door.is_unlocked = !snapshot.locked;
+        }
+
+        // Doors that never moved are still in their closed pose
+        let closed_pose = *door.closed_pose.get_or_insert(Isometry2d::new(
+            transform.translation.xy(),
+            Rot2::radians(transform.rotation.to_euler(EulerRot::ZYX).0),
+        ));
+        let pose = door.pose(closed_pose);
+
+        entity.insert((
+            snapshot.state,
+            Transform {
+                translation: pose.translation.extend(transform.translation.z),
+                rotation: Quat::from_rotation_z(pose.rotation.as_radians()),
+                ..transform
+            },
+            Position(pose.translation),
+            Rotation::radians(pose.rotation.as_radians()),
+        ));
+
+        if snapshot.state == DoorState::Open {
+            entity.insert(DoorIsOpen);
+        } else {
+            entity.remove::<DoorIsOpen>();
+        }
+
+        if let Some(mut interactable) = entity.get_mut::<Interactable>() {
+            interactable.prompt = snapshot.state.prompt().to_owned();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use crate::persistence::{
+        PersistenceAppExt, PersistencePlugin, PersistentId, WorldState, capture_world_state,
+        restore_world_state,
+    };
+
+    use super::{super::DoorMotion, *};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(PersistencePlugin)
+            .register_persistent::<Door>();
+        app
+    }
+
+    /// Hinged door closed at `(300, 0)` that opens into `(260, 40)` at a right angle.
+    fn hinged_door() -> impl Bundle {
+        (
+            PersistentId::new("level/door"),
+            Door::new(DoorMotion::Hinge {
+                pivot: vec2(-40.0, 0.0),
+                angle: FRAC_PI_2,
+                swing_away: false,
+            }),
+            Transform::from_xyz(300.0, 0.0, 1.0),
+        )
+    }
+
+    fn assert_pose(world: &World, entity: Entity, translation: Vec2, angle: f32) {
+        let transform = world.get::<Transform>(entity).unwrap();
+        let position = world.get::<Position>(entity).unwrap();
+        let rotation = world.get::<Rotation>(entity).unwrap();
+
+        assert!(transform.translation.xy().abs_diff_eq(translation, 1e-3));
+        assert_eq!(transform.translation.z, 1.0);
+        assert!((transform.rotation.to_euler(EulerRot::ZYX).0 - angle).abs() < 1e-4);
+        assert!(position.0.abs_diff_eq(translation, 1e-3));
+        assert!((rotation.as_radians() - angle).abs() < 1e-4);
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_ron() {
+        let snapshot = DoorSnapshot {
+            state: DoorState::Closing,
+            progress: 0.25,
+            swing: -1.0,
+            locked: true,
+        };
+
+        let ron = ron::to_string(&snapshot).unwrap();
+
+        assert_eq!(ron::from_str::<DoorSnapshot>(&ron), Ok(snapshot));
+    }
+
+    #[test]
+    fn respawned_doors_are_restored_as_they_were_left() {
+        let mut app = app();
+        let world = app.world_mut();
+
+        let entity = world.spawn(hinged_door()).id();
+        world.flush();
+        let mut door = world.get_mut::<Door>(entity).unwrap();
+        door.progress = 1.0;
+        door.closed_pose = Some(Isometry2d::from_xy(300.0, 0.0));
+        world.entity_mut(entity).insert((
+            DoorState::Open,
+            Transform::from_xyz(260.0, 40.0, 1.0).with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+        ));
+        world.despawn(entity);
+
+        // The state survives a save file as well
+        let ron = world.resource::<WorldState>().to_ron().unwrap();
+        let loaded = WorldState::from_ron(&ron).unwrap();
+        assert_eq!(&loaded, world.resource::<WorldState>());
+        world.insert_resource(loaded);
+
+        // The respawned door starts out closed and never moved, so its closed pose is taken from
+        // its transform
+        let entity = world.spawn(hinged_door()).id();
+        world.flush();
+
+        assert_eq!(world.get::<DoorState>(entity), Some(&DoorState::Open));
+        assert!(world.entity(entity).contains::<DoorIsOpen>());
+        assert_eq!(world.get::<Door>(entity).unwrap().progress, 1.0);
+        assert_eq!(world.get::<Interactable>(entity).unwrap().prompt, "Close");
+        assert_pose(world, entity, vec2(260.0, 40.0), FRAC_PI_2);
+    }
+
+    #[test]
+    fn restoring_a_door_that_moved_keeps_its_closed_pose() {
+        let mut app = app();
+        let world = app.world_mut();
+
+        let entity = world.spawn(hinged_door()).id();
+        world.flush();
+        capture_world_state(world);
+        let closed = world.resource::<WorldState>().clone();
+        assert!(closed.contains("level/door"));
+
+        let mut door = world.get_mut::<Door>(entity).unwrap();
+        door.progress = 1.0;
+        door.closed_pose = Some(Isometry2d::from_xy(300.0, 0.0));
+        world.entity_mut(entity).insert((
+            DoorState::Open,
+            Transform::from_xyz(260.0, 40.0, 1.0).with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+        ));
+
+        world.insert_resource(closed);
+        restore_world_state(world);
+
+        assert_eq!(world.get::<DoorState>(entity), Some(&DoorState::Closed));
+        assert!(!world.entity(entity).contains::<DoorIsOpen>());
+        assert_eq!(world.get::<Door>(entity).unwrap().progress, 0.0);
+        assert_pose(world, entity, vec2(300.0, 0.0), 0.0);
+    }
+}
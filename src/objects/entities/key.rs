@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::{
+    interaction::{Interactable, InteractionSystems, InteractionsWith},
+    objects::characters::Inventory,
+};
+
+pub struct KeyPlugin;
+
+impl Plugin for KeyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, pick_up_keys.after(InteractionSystems));
+    }
+}
+
+/// Key lying in the world, which goes into the [`Inventory`] of the first character that interacts
+/// with it, see [`LockRequirement::Key`](super::LockRequirement::Key).
+#[derive(Component, Clone, Debug)]
+#[require(Interactable = Interactable::new("Pick up"))]
+pub struct KeyPickup(pub String);
+
+fn pick_up_keys(
+    mut interactions: InteractionsWith<KeyPickup>,
+    keys: Query<&KeyPickup>,
+    mut inventories: Query<&mut Inventory>,
+    mut commands: Commands,
+) {
+    for interaction in interactions.read() {
+        let (Ok(key), Ok(mut inventory)) = (
+            keys.get(interaction.target),
+            inventories.get_mut(interaction.interactor),
+        ) else {
+            continue;
+        };
+
+        inventory.add_key(key.0.clone());
+        commands.entity(interaction.target).try_despawn();
+    }
+}
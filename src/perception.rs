@@ -0,0 +1,519 @@
+//! Vision of NPCs that builds up awareness of the targets they see.
+//!
+//! No character uses this yet, so [`PerceptionPlugin`] has to be added by whatever spawns the
+//! first [`Perception`].
+
+use avian2d::prelude::*;
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+use derive::TriggerMessage;
+
+use crate::sector::{
+    Sector, ShapeTrigger, TriggerChannel, TriggerDetectionSystems, TriggerOcclusion, TriggerPlugin,
+    TriggerTransition,
+};
+
+pub struct PerceptionPlugin;
+
+impl Plugin for PerceptionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(TriggerPlugin::<VisionMessage>::default())
+            .add_message::<AwarenessChanged>()
+            .add_systems(
+                FixedUpdate,
+                update_perception
+                    .in_set(PerceptionSystems)
+                    .after(TriggerDetectionSystems),
+            );
+    }
+}
+
+/// Perception update, [`AwarenessChanged`] messages are written by systems in this set.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PerceptionSystems;
+
+/// How aware an observer is of a single target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AwarenessState {
+    /// The target hasn't been noticed.
+    #[default]
+    Unaware,
+    /// The target has been glimpsed but not recognized yet.
+    Suspicious,
+    /// The target has been recognized and is still in sight, or was only just lost out of sight.
+    Alerted,
+    /// The target was recognized but hasn't been seen for a while.
+    Lost,
+}
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct AwarenessChanged {
+    /// Entity holding the [`Perception`].
+    pub observer: Entity,
+    pub target: Entity,
+    pub previous: AwarenessState,
+    pub state: AwarenessState,
+    /// Position of the target when it was last seen by the observer.
+    pub last_known_position: Vec2,
+}
+
+/// How brightly a target is lit, from `0.0` (complete darkness) to `1.0` (fully lit). Dark targets
+/// build up awareness slower in the peripheral cone of a [`Perception`], targets without this
+/// component count as fully lit.
+#[derive(Component, Clone, Copy, Debug, Deref, DerefMut)]
+pub struct Illumination(pub f32);
+
+/// Awareness of a [`Perception`] towards a single target.
+#[derive(Clone, Copy, Debug)]
+pub struct TargetAwareness {
+    level: f32,
+    state: AwarenessState,
+    last_known_position: Vec2,
+    /// Seconds since the target was last seen, zero while it is in sight.
+    time_unseen: f32,
+}
+
+impl TargetAwareness {
+    /// Awareness level in `[0, 1]`, the target is recognized once it reaches `1.0`.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    pub fn state(&self) -> AwarenessState {
+        self.state
+    }
+
+    pub fn last_known_position(&self) -> Vec2 {
+        self.last_known_position
+    }
+
+    pub fn is_in_sight(&self) -> bool {
+        self.time_unseen == 0.0
+    }
+}
+
+/// Written by the triggers of the [`VisionCone`]s when targets come into or go out of sight of a
+/// single cone, regardless of their awareness.
+#[derive(Message, TriggerMessage)]
+pub struct VisionMessage {
+    pub transition: TriggerTransition,
+    /// Entity of the [`VisionCone`].
+    pub trigger: Entity,
+    pub target: Entity,
+}
+
+/// Vision cone of the [`Perception`] on the parent entity. The cone is a [`ShapeTrigger`] whose
+/// [`VisionMessage`] channel detects the targets in sight, see [`VisionCone::bundle`].
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VisionCone {
+    /// Targets in this cone are recognized instantly.
+    Near,
+    /// Awareness of targets in this cone builds up over time.
+    Peripheral,
+}
+
+impl VisionCone {
+    /// Creates the cone along with the trigger that sees through it.
+    ///
+    /// * `sector` - Shape of the cone, relative to the transform of the cone entity
+    /// * `target_mask` - Layers of the entities that can be seen
+    /// * `obstacle_mask` - Layers of the colliders that block line of sight
+    pub fn bundle(
+        self,
+        sector: Sector,
+        target_mask: LayerMask,
+        obstacle_mask: LayerMask,
+    ) -> impl Bundle {
+        (
+            self,
+            sector,
+            ShapeTrigger::new()
+                .with_channel(TriggerChannel::new::<VisionMessage>(target_mask))
+                .with_occlusion(TriggerOcclusion::new(obstacle_mask)),
+        )
+    }
+}
+
+/// Vision of an NPC through the [`VisionCone`]s among its children. Targets in the near cone are
+/// recognized instantly, while awareness of targets in the peripheral cone builds up over time
+/// depending on how well they are lit and how fast they move.
+#[derive(Component)]
+#[require(Transform)]
+pub struct Perception {
+    /// Awareness per second gained in the peripheral cone by a fully lit and running target.
+    pub build_rate: f32,
+    /// Awareness per second lost while a target is out of sight.
+    pub decay_rate: f32,
+    /// Awareness level at which the observer becomes suspicious.
+    pub suspicious_threshold: f32,
+    /// Seconds an alerted observer keeps track of a target after losing sight of it.
+    pub lose_delay: f32,
+    /// Fraction of the build rate that applies to a target standing still.
+    pub stationary_factor: f32,
+    /// Speed from which targets build up awareness at the full rate.
+    pub running_speed: f32,
+    targets: EntityHashMap<TargetAwareness>,
+}
+
+impl Perception {
+    pub fn new() -> Self {
+        Self {
+            build_rate: 1.0,
+            decay_rate: 0.25,
+            suspicious_threshold: 0.3,
+            lose_delay: 3.0,
+            stationary_factor: 0.25,
+            running_speed: 200.0,
+            targets: EntityHashMap::default(),
+        }
+    }
+
+    pub fn with_awareness_rates(self, build_rate: f32, decay_rate: f32) -> Self {
+        Self {
+            build_rate,
+            decay_rate,
+            ..self
+        }
+    }
+
+    pub fn with_suspicious_threshold(self, suspicious_threshold: f32) -> Self {
+        Self {
+            suspicious_threshold,
+            ..self
+        }
+    }
+
+    pub fn with_lose_delay(self, lose_delay: f32) -> Self {
+        Self { lose_delay, ..self }
+    }
+
+    pub fn with_motion_sensitivity(self, stationary_factor: f32, running_speed: f32) -> Self {
+        Self {
+            stationary_factor,
+            running_speed,
+            ..self
+        }
+    }
+
+    /// Awareness towards a target, or [`None`] if the observer is unaware of it.
+    pub fn awareness(&self, target: Entity) -> Option<&TargetAwareness> {
+        self.targets.get(&target)
+    }
+
+    /// Every target the observer is aware of.
+    pub fn targets(&self) -> impl Iterator<Item = (Entity, &TargetAwareness)> {
+        self.targets
+            .iter()
+            .map(|(entity, awareness)| (*entity, awareness))
+    }
+
+    /// Multiplier of the build rate for a target moving at `speed`.
+    fn motion_factor(&self, speed: f32) -> f32 {
+        let running = (speed / self.running_speed.max(f32::EPSILON)).min(1.0);
+        self.stationary_factor + (1.0 - self.stationary_factor) * running
+    }
+
+    fn next_state(&self, awareness: &TargetAwareness) -> AwarenessState {
+        if awareness.level >= 1.0 && awareness.is_in_sight() {
+            return AwarenessState::Alerted;
+        }
+
+        match awareness.state {
+            AwarenessState::Alerted if awareness.time_unseen >= self.lose_delay => {
+                AwarenessState::Lost
+            }
+            AwarenessState::Alerted => AwarenessState::Alerted,
+            _ if awareness.level <= 0.0 => AwarenessState::Unaware,
+            AwarenessState::Unaware if awareness.level >= self.suspicious_threshold => {
+                AwarenessState::Suspicious
+            }
+            state => state,
+        }
+    }
+
+    /// Updates the awareness of every target from the targets in sight for `delta` seconds, and
+    /// returns the changes of their states.
+    fn perceive(
+        &mut self,
+        observer: Entity,
+        sightings: &EntityHashMap<Sighting>,
+        delta: f32,
+    ) -> Vec<AwarenessChanged> {
+        for (entity, sighting) in sightings {
+            // Targets that just came into sight count as moving
+            let speed = match self.targets.get(entity) {
+                Some(awareness) if awareness.is_in_sight() && delta > 0.0 => {
+                    sighting.position.distance(awareness.last_known_position) / delta
+                }
+                _ => f32::INFINITY,
+            };
+            let rate = self.build_rate * sighting.light * self.motion_factor(speed);
+
+            let awareness = self.targets.entry(*entity).or_insert(TargetAwareness {
+                level: 0.0,
+                state: AwarenessState::Unaware,
+                last_known_position: sighting.position,
+                time_unseen: 0.0,
+            });
+            awareness.level = match sighting.cone {
+                VisionCone::Near => 1.0,
+                VisionCone::Peripheral => (awareness.level + rate * delta).min(1.0),
+            };
+            awareness.last_known_position = sighting.position;
+            awareness.time_unseen = 0.0;
+        }
+
+        for (entity, awareness) in self.targets.iter_mut() {
+            if sightings.contains_key(entity) {
+                continue;
+            }
+
+            awareness.time_unseen += delta;
+            // Alerted observers stay fully aware until they lose track of the target
+            if awareness.state != AwarenessState::Alerted {
+                awareness.level = (awareness.level - self.decay_rate * delta).max(0.0);
+            }
+        }
+
+        let mut changes = Vec::new();
+        for (entity, awareness) in self.targets.iter() {
+            let state = self.next_state(awareness);
+            if state != awareness.state {
+                changes.push(AwarenessChanged {
+                    observer,
+                    target: *entity,
+                    previous: awareness.state,
+                    state,
+                    last_known_position: awareness.last_known_position,
+                });
+            }
+        }
+
+        for change in &changes {
+            if let Some(awareness) = self.targets.get_mut(&change.target) {
+                awareness.state = change.state;
+            }
+        }
+
+        self.targets.retain(|_, awareness| {
+            awareness.state != AwarenessState::Unaware || awareness.level > 0.0
+        });
+
+        changes
+    }
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Target in sight of an observer.
+#[derive(Clone, Copy, Debug)]
+struct Sighting {
+    /// Cone that sees the target, the near cone if both do.
+    cone: VisionCone,
+    position: Vec2,
+    /// [`Illumination`] of the target.
+    light: f32,
+}
+
+fn update_perception(
+    time: Res<Time>,
+    targets: Query<(&Position, Option<&Illumination>)>,
+    cones: Query<(&VisionCone, &ShapeTrigger)>,
+    mut observers: Query<(Entity, &mut Perception, &Children)>,
+    mut message_writer: MessageWriter<AwarenessChanged>,
+) {
+    let delta = time.delta_secs();
+
+    for (observer, mut perception, children) in &mut observers {
+        let mut sightings = EntityHashMap::default();
+        for (cone, trigger) in cones.iter_many(children) {
+            let Some(channel) = trigger.channel::<VisionMessage>() else {
+                continue;
+            };
+
+            for entity in channel.detected().keys() {
+                let Ok((position, illumination)) = targets.get(*entity) else {
+                    continue;
+                };
+                if *entity == observer {
+                    continue;
+                }
+
+                let light = illumination.map_or(1.0, |illumination| illumination.0.clamp(0.0, 1.0));
+                // Being seen by the near cone wins over the peripheral one
+                sightings
+                    .entry(*entity)
+                    .and_modify(|sighting: &mut Sighting| {
+                        if *cone == VisionCone::Near {
+                            sighting.cone = VisionCone::Near;
+                        }
+                    })
+                    .or_insert(Sighting {
+                        cone: *cone,
+                        position: position.0,
+                        light,
+                    });
+            }
+        }
+
+        for message in perception.perceive(observer, &sightings, delta) {
+            message_writer.write(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OBSERVER: Entity = Entity::PLACEHOLDER;
+
+    fn target() -> Entity {
+        Entity::from_raw_u32(1).unwrap()
+    }
+
+    fn sighting(cone: VisionCone, position: Vec2, light: f32) -> EntityHashMap<Sighting> {
+        EntityHashMap::from_iter([(
+            target(),
+            Sighting {
+                cone,
+                position,
+                light,
+            },
+        )])
+    }
+
+    /// Perception whose peripheral build rate doesn't depend on the motion of the target.
+    fn perception() -> Perception {
+        Perception::new()
+            .with_awareness_rates(1.0, 0.25)
+            .with_suspicious_threshold(0.5)
+            .with_lose_delay(2.0)
+            .with_motion_sensitivity(1.0, 200.0)
+    }
+
+    fn transitions(changes: &[AwarenessChanged]) -> Vec<(AwarenessState, AwarenessState)> {
+        changes
+            .iter()
+            .map(|change| (change.previous, change.state))
+            .collect()
+    }
+
+    #[test]
+    fn unaware_observers_become_suspicious_at_the_threshold() {
+        let mut perception = perception();
+        let peripheral = sighting(VisionCone::Peripheral, Vec2::ZERO, 1.0);
+
+        assert!(perception.perceive(OBSERVER, &peripheral, 0.25).is_empty());
+        let awareness = perception.awareness(target()).unwrap();
+        assert_eq!(awareness.level(), 0.25);
+        assert_eq!(awareness.state(), AwarenessState::Unaware);
+
+        let changes = perception.perceive(OBSERVER, &peripheral, 0.25);
+        assert_eq!(
+            transitions(&changes),
+            [(AwarenessState::Unaware, AwarenessState::Suspicious)]
+        );
+        assert_eq!(perception.awareness(target()).unwrap().level(), 0.5);
+    }
+
+    #[test]
+    fn near_cone_alerts_instantly() {
+        let mut perception = perception();
+
+        let changes = perception.perceive(
+            OBSERVER,
+            &sighting(VisionCone::Near, vec2(10.0, 0.0), 0.0),
+            0.1,
+        );
+
+        assert_eq!(
+            transitions(&changes),
+            [(AwarenessState::Unaware, AwarenessState::Alerted)]
+        );
+        assert_eq!(changes[0].last_known_position, vec2(10.0, 0.0));
+        assert_eq!(perception.awareness(target()).unwrap().level(), 1.0);
+    }
+
+    #[test]
+    fn alerted_observers_lose_targets_after_the_lose_delay() {
+        let mut perception = perception();
+        let unseen = EntityHashMap::default();
+
+        perception.perceive(OBSERVER, &sighting(VisionCone::Near, Vec2::ZERO, 1.0), 0.1);
+
+        assert!(perception.perceive(OBSERVER, &unseen, 1.0).is_empty());
+        let awareness = perception.awareness(target()).unwrap();
+        assert_eq!(awareness.state(), AwarenessState::Alerted);
+        assert_eq!(awareness.level(), 1.0, "alerted observers don't forget");
+        assert!(!awareness.is_in_sight());
+
+        let changes = perception.perceive(OBSERVER, &unseen, 1.0);
+        assert_eq!(
+            transitions(&changes),
+            [(AwarenessState::Alerted, AwarenessState::Lost)]
+        );
+    }
+
+    #[test]
+    fn awareness_decays_back_to_unaware_and_forgets_the_target() {
+        let mut perception = perception();
+        let unseen = EntityHashMap::default();
+
+        perception.perceive(
+            OBSERVER,
+            &sighting(VisionCone::Peripheral, Vec2::ZERO, 1.0),
+            0.5,
+        );
+        assert_eq!(
+            perception.awareness(target()).unwrap().state(),
+            AwarenessState::Suspicious
+        );
+
+        assert!(perception.perceive(OBSERVER, &unseen, 1.0).is_empty());
+        assert_eq!(perception.awareness(target()).unwrap().level(), 0.25);
+
+        let changes = perception.perceive(OBSERVER, &unseen, 1.0);
+        assert_eq!(
+            transitions(&changes),
+            [(AwarenessState::Suspicious, AwarenessState::Unaware)]
+        );
+        assert!(perception.awareness(target()).is_none());
+        assert_eq!(perception.targets().count(), 0);
+    }
+
+    #[test]
+    fn peripheral_awareness_builds_slower_for_dark_and_slow_targets() {
+        let mut perception = Perception::new()
+            .with_awareness_rates(1.0, 0.25)
+            .with_motion_sensitivity(0.25, 200.0);
+        let level = |perception: &Perception| perception.awareness(target()).unwrap().level();
+
+        // Targets that just came into sight count as running
+        perception.perceive(
+            OBSERVER,
+            &sighting(VisionCone::Peripheral, Vec2::ZERO, 0.5),
+            0.5,
+        );
+        assert_eq!(level(&perception), 0.25);
+
+        // Standing still
+        perception.perceive(
+            OBSERVER,
+            &sighting(VisionCone::Peripheral, Vec2::ZERO, 0.5),
+            0.5,
+        );
+        assert_eq!(level(&perception), 0.3125);
+
+        // Moving at half the running speed
+        perception.perceive(
+            OBSERVER,
+            &sighting(VisionCone::Peripheral, vec2(50.0, 0.0), 0.5),
+            0.5,
+        );
+        assert_eq!(level(&perception), 0.46875);
+    }
+}
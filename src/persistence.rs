@@ -0,0 +1,208 @@
+//! Object state that outlives the entities holding it. Objects are identified by a
+//! [`PersistentId`] instead of their [`Entity`], whose state is saved into the [`WorldState`] when
+//! they are despawned and restored when an object with the same id is spawned again, so that
+//! leaving and re-entering a level keeps it as it was left. The [`WorldState`] can also be written
+//! to and read from RON to save and load the game.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    ecs::world::{DeferredWorld, EntityRef, EntityWorldMut},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+pub struct PersistencePlugin;
+
+impl Plugin for PersistencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldState>()
+            .init_resource::<PersistentTypes>()
+            .add_observer(restore_spawned_objects)
+            .add_observer(save_despawned_objects);
+    }
+}
+
+/// Identifies an object across level reloads and saves, has to be unique among all objects of the
+/// game, e.g. `"level/north_door"`.
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash, Deref)]
+pub struct PersistentId(pub String);
+
+impl PersistentId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+}
+
+/// Component whose state is kept in the [`WorldState`], see
+/// [`PersistenceAppExt::register_persistent`].
+pub trait Persistent: Component {
+    /// Key the state is stored under for each object, unique among all persistent components.
+    const KEY: &'static str;
+
+    type State: Serialize + DeserializeOwned;
+
+    /// Returns [`None`] if there is nothing to save.
+    fn save(entity: EntityRef) -> Option<Self::State>;
+
+    /// Applies a saved state to an entity with this component.
+    fn restore(entity: EntityWorldMut, state: Self::State);
+}
+
+pub trait PersistenceAppExt {
+    fn register_persistent<T: Persistent>(&mut self) -> &mut Self;
+}
+
+impl PersistenceAppExt for App {
+    fn register_persistent<T: Persistent>(&mut self) -> &mut Self {
+        self.init_resource::<PersistentTypes>()
+            .world_mut()
+            .resource_mut::<PersistentTypes>()
+            .push(PersistentType {
+                key: T::KEY,
+                save: save_erased::<T>,
+                restore: restore_erased::<T>,
+            });
+        self
+    }
+}
+
+/// Saved state of every persistent object, by [`PersistentId`] and [`Persistent::KEY`]. States
+/// are kept as RON so that the snapshot doesn't need to know their types.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct WorldState {
+    objects: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl WorldState {
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.objects.contains_key(id)
+    }
+
+    /// Forgets the saved state of an object, it is spawned as defined by its level next time.
+    pub fn forget(&mut self, id: &str) {
+        self.objects.remove(id);
+    }
+
+    pub fn clear(&mut self) {
+        self.objects.clear();
+    }
+}
+
+#[derive(Clone, Copy)]
+struct PersistentType {
+    key: &'static str,
+    save: fn(EntityRef) -> Option<String>,
+    restore: fn(EntityWorldMut, &str),
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct PersistentTypes(Vec<PersistentType>);
+
+fn save_erased<T: Persistent>(entity: EntityRef) -> Option<String> {
+    if !entity.contains::<T>() {
+        return None;
+    }
+
+    match ron::to_string(&T::save(entity)?) {
+        Ok(state) => Some(state),
+        Err(error) => {
+            warn!("Failed to save {} of {}: {error}", T::KEY, entity.id());
+            None
+        }
+    }
+}
+
+fn restore_erased<T: Persistent>(entity: EntityWorldMut, state: &str) {
+    if !entity.contains::<T>() {
+        return;
+    }
+
+    match ron::from_str(state) {
+        Ok(state) => T::restore(entity, state),
+        Err(error) => warn!("Failed to restore {} of {}: {error}", T::KEY, entity.id()),
+    }
+}
+
+/// Saves the state of an object into the [`WorldState`], replacing what was saved before.
+fn save_object(world: &mut DeferredWorld, entity: Entity) {
+    let types = world.resource::<PersistentTypes>().0.clone();
+    let entity_ref = world.entity(entity);
+    let Some(id) = entity_ref.get::<PersistentId>() else {
+        return;
+    };
+
+    let states: BTreeMap<_, _> = types
+        .iter()
+        .filter_map(|ty| Some((ty.key.to_owned(), (ty.save)(entity_ref)?)))
+        .collect();
+    let id = id.0.clone();
+
+    world
+        .resource_mut::<WorldState>()
+        .objects
+        .insert(id, states);
+}
+
+/// Applies the saved state of an object, if there is any.
+fn restore_object(world: &mut World, entity: Entity) {
+    let Some(id) = world.get::<PersistentId>(entity) else {
+        return;
+    };
+    let Some(states) = world.resource::<WorldState>().objects.get(&id.0).cloned() else {
+        return;
+    };
+    let types = world.resource::<PersistentTypes>().0.clone();
+
+    for ty in types {
+        if let Some(state) = states.get(ty.key)
+            && let Ok(entity) = world.get_entity_mut(entity)
+        {
+            (ty.restore)(entity, state);
+        }
+    }
+}
+
+/// Saves the state of every persistent object into the [`WorldState`], e.g. before writing it to
+/// a save file.
+pub fn capture_world_state(world: &mut World) {
+    let entities: Vec<_> = world
+        .query_filtered::<Entity, With<PersistentId>>()
+        .iter(world)
+        .collect();
+
+    let mut world = DeferredWorld::from(world);
+    for entity in entities {
+        save_object(&mut world, entity);
+    }
+}
+
+/// Applies the [`WorldState`] to every persistent object, e.g. after loading a save file.
+pub fn restore_world_state(world: &mut World) {
+    let entities: Vec<_> = world
+        .query_filtered::<Entity, With<PersistentId>>()
+        .iter(world)
+        .collect();
+
+    for entity in entities {
+        restore_object(world, entity);
+    }
+}
+
+/// Restores objects once the rest of their components are inserted.
+fn restore_spawned_objects(add: On<Add, PersistentId>, mut commands: Commands) {
+    let entity = add.entity;
+    commands.queue(move |world: &mut World| restore_object(world, entity));
+}
+
+fn save_despawned_objects(despawn: On<Despawn, PersistentId>, mut world: DeferredWorld) {
+    save_object(&mut world, despawn.entity);
+}
@@ -8,6 +8,8 @@ pub enum ObjectLayer {
     Obstacle,
     Player,
     Door,
+    /// Objects other than doors that characters can interact with.
+    Interactable,
 }
 
 /// Awd
@@ -1,168 +1,1365 @@
-use std::{f32::consts::PI, marker::PhantomData};
+use std::{any::TypeId, cmp::Reverse, f32::consts::PI, marker::PhantomData, time::Duration};
 
 use avian2d::prelude::*;
 use bevy::{
-    asset::RenderAssetUsages,
-    mesh::{Indices, PrimitiveTopology},
+    ecs::{change_detection::Tick, entity::EntityHashMap, system::SystemParam},
+    platform::time::Instant,
     prelude::*,
 };
 
-use crate::{
-    math::{self, rotate_vec2},
-    physics::ObjectLayer,
-};
+use crate::math::{self, rotate_vec2};
 
-#[derive(Clone, Debug)]
+mod shapes;
+
+pub use shapes::*;
+
+/// Largest distance between two rays along the arc of a sector's visibility polygon, in world
+/// units. Obstacles narrower than this may be missed by the polygon.
+const VISIBILITY_SAMPLE_SPACING: f32 = 4.0;
+
+/// Circular sector shape of a [`ShapeTrigger`], relative to the transform of its entity.
+#[derive(Component, Clone, Debug)]
 pub struct Sector {
     pub radius: f32,
     pub arc_angle: f32,
     pub center_angle: f32,
     pub min_edges_per_radian: f32,
-    mesh_points: Vec<Vec2>,
-    triangle_indices: Vec<[u32; 3]>,
 }
 
 impl Sector {
     pub fn new(radius: f32, arc_angle: f32, center_angle: f32, min_edges_per_radian: f32) -> Self {
-        let half_span = arc_angle.clamp(0.0, 2.0 * PI) / 2.0;
-        let unit = rotate_vec2(Vec2::X, center_angle - half_span);
-        let initial_position = radius * unit;
+        Sector {
+            radius,
+            arc_angle,
+            center_angle,
+            min_edges_per_radian,
+        }
+    }
+
+    /// Number of points along the arc of the display mesh. The arc always has at least its two
+    /// edge points, which keeps the step angle finite for arcs that are (close to) zero.
+    fn arc_point_count(&self) -> usize {
+        let clamped_arc_angle = self.arc_angle.clamp(0.0, 2.0 * PI);
+        ((clamped_arc_angle * self.min_edges_per_radian).ceil() as usize).max(2)
+    }
 
-        let arc_point_count = (arc_angle * min_edges_per_radian).ceil() as usize;
-        let arc_angle = arc_angle / (arc_point_count - 1) as f32;
+    /// Points of the display mesh: the origin followed by the points along the arc.
+    fn mesh_points(&self) -> Vec<Vec2> {
+        let clamped_arc_angle = self.arc_angle.clamp(0.0, 2.0 * PI);
+        let half_span = clamped_arc_angle / 2.0;
+        let unit = rotate_vec2(Vec2::X, self.center_angle - half_span);
+        let initial_position = self.radius * unit;
+
+        let arc_point_count = self.arc_point_count();
+        let step_angle = clamped_arc_angle / (arc_point_count - 1) as f32;
 
         let mut mesh_points: Vec<Vec2> = vec![Vec2::ZERO; arc_point_count + 1];
         mesh_points[1] = initial_position;
         for i in 1..arc_point_count {
-            mesh_points[i + 1] = math::rotate_vec2(mesh_points[1], arc_angle * i as f32);
+            mesh_points[i + 1] = math::rotate_vec2(mesh_points[1], step_angle * i as f32);
         }
 
-        let mut triangle_indices: Vec<[u32; 3]> = vec![[0, 0, 0]; arc_point_count - 1];
-        for i in 0..(arc_point_count - 1) {
-            triangle_indices[i][0] = 0;
-            triangle_indices[i][1] = (i + 1) as u32;
-            triangle_indices[i][2] = (i + 2) as u32;
+        mesh_points
+    }
+}
+
+impl TriggerShape for Sector {
+    fn bounding_radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Tests whether a point relative to the sector origin lies inside of the sector.
+    fn contains_point(&self, point: Vec2) -> bool {
+        if point.length_squared() > self.radius * self.radius {
+            return false;
         }
 
-        Sector {
-            radius,
-            arc_angle,
-            center_angle,
-            min_edges_per_radian,
-            mesh_points,
-            triangle_indices,
+        let half_span = self.arc_angle.clamp(0.0, 2.0 * PI) / 2.0;
+        rotate_vec2(point, -self.center_angle).to_angle().abs() <= half_span
+    }
+
+    fn forward_angle(&self) -> f32 {
+        self.center_angle
+    }
+
+    fn parameters(&self) -> SectorParameters {
+        SectorParameters {
+            radius: self.radius,
+            arc_angle: self.arc_angle,
+            center_angle: self.center_angle,
         }
     }
 
-    fn into_mesh(&self) -> Mesh {
-        let mesh_points: Vec<Vec3> = self
-            .mesh_points
-            .clone()
-            .iter()
-            .map(|v| v.extend(0.0))
+    fn to_mesh(&self) -> Mesh {
+        let mesh_points = self.mesh_points();
+        let triangle_indices = fan_indices(mesh_points.len());
+        triangle_mesh(&mesh_points, triangle_indices)
+    }
+
+    /// Sectors have no collider since their intersection test is analytic, see
+    /// [`Sector::intersect_collider`](TriggerShape::intersect_collider).
+    fn to_collider(&self) -> Option<Collider> {
+        None
+    }
+
+    /// Fan through the visible end of one ray per sample along the arc. The samples are at most
+    /// [`VISIBILITY_SAMPLE_SPACING`] apart and never fewer than the points of the full mesh.
+    fn to_visibility_mesh(&self, visible_distance: impl Fn(Dir2, f32) -> f32) -> Option<Mesh> {
+        let arc_angle = self.arc_angle.clamp(0.0, 2.0 * PI);
+        let gap_count = (arc_angle * self.radius / VISIBILITY_SAMPLE_SPACING).ceil() as usize;
+        let sample_count = (gap_count + 1).max(self.arc_point_count());
+        let step_angle = arc_angle / (sample_count - 1) as f32;
+        let start_angle = self.center_angle - arc_angle / 2.0;
+
+        let mesh_points: Vec<Vec2> = std::iter::once(Vec2::ZERO)
+            .chain((0..sample_count).map(|i| {
+                let direction = Rot2::radians(start_angle + step_angle * i as f32) * Dir2::X;
+                direction * visible_distance(direction, self.radius).clamp(0.0, self.radius)
+            }))
             .collect();
+        let triangle_indices = fan_indices(mesh_points.len());
+        Some(triangle_mesh(&mesh_points, triangle_indices))
+    }
 
-        Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all())
-            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, mesh_points)
-            .with_inserted_indices(Indices::U32(self.triangle_indices.clone().into_flattened()))
+    /// Exact overlap test that doesn't need the collider of the sector.
+    ///
+    /// The closest point is tested against the radius and the arc first. If it lies outside of
+    /// the arc, an overlapping collider has to cross one of the straight edges of the sector,
+    /// which is checked with a raycast along each edge. This is exact for convex colliders.
+    fn intersect_collider(
+        &self,
+        _shape_collider: Option<&Collider>,
+        origin: Vec2,
+        angle: f32,
+        (collider, position, rotation): (&Collider, &Position, &Rotation),
+    ) -> Option<Vec2> {
+        let contact_point = collider.project_point(*position, *rotation, origin, true).0;
+        let to_contact = contact_point - origin;
+
+        if to_contact.length_squared() > self.radius * self.radius {
+            return None;
+        }
+        if self.contains_point(rotate_vec2(to_contact, -angle)) {
+            return Some(contact_point);
+        }
+
+        let half_span = self.arc_angle.clamp(0.0, 2.0 * PI) / 2.0;
+        [self.center_angle - half_span, self.center_angle + half_span]
+            .into_iter()
+            .any(|edge_angle| {
+                let direction = rotate_vec2(Vec2::X, angle + edge_angle);
+                collider.intersects_ray(*position, *rotation, origin, direction, self.radius)
+            })
+            .then_some(contact_point)
+    }
+}
+
+/// Triangle indices of a fan around the first of `point_count` points.
+fn fan_indices(point_count: usize) -> Vec<u32> {
+    (0..(point_count as u32).saturating_sub(2))
+        .flat_map(|i| [0, i + 1, i + 2])
+        .collect()
+}
+
+/// Line of sight requirement for a [`ShapeTrigger`]. Detected entities only count if they can be
+/// seen from the sector origin without any collider on the blocking layers in between.
+#[derive(Clone, Debug)]
+pub struct TriggerOcclusion {
+    pub mask: LayerMask,
+    /// Whether the displayed mesh is replaced by the visibility polygon of the shape, see
+    /// [`TriggerShape::to_visibility_mesh`].
+    pub clip_mesh: bool,
+}
+
+impl TriggerOcclusion {
+    pub fn new(mask: LayerMask) -> Self {
+        Self {
+            mask,
+            clip_mesh: false,
+        }
     }
 
-    fn into_collider(&self) -> Collider {
-        Collider::trimesh(self.mesh_points.clone(), self.triangle_indices.clone())
+    pub fn with_clipped_mesh(self, clip_mesh: bool) -> Self {
+        Self { clip_mesh, ..self }
     }
 }
 
-// TODO: rename this to a more general message trait
-pub trait SectorMessage: Send + Sync + Message + From<Entity> + 'static {} // Plus L plus Ratio
+/// How a detection relates to the detections of the previous update of the same trigger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerTransition {
+    /// The entity was not detected by the trigger on the previous update.
+    Enter,
+    /// The entity was already detected on the previous update. Only sent by triggers that have
+    /// stay messages enabled.
+    Stay,
+    /// The entity was detected on the previous update but not anymore, either because it left the
+    /// sector or because it was despawned.
+    Exit,
+}
 
-// TODO: rename this to sector sensor or some bullshit like that
+/// Circular sector around the trigger origin that the detections of a shape are measured against,
+/// see [`TriggerShape::parameters`].
+#[derive(Clone, Copy, Debug)]
+pub struct SectorParameters {
+    pub radius: f32,
+    pub arc_angle: f32,
+    pub center_angle: f32,
+}
+
+/// Everything known about a single detection of a [`ShapeTrigger`], which every
+/// [`TriggerMessage`] is built from.
+#[derive(Clone, Copy, Debug)]
+pub struct TriggerContext {
+    pub transition: TriggerTransition,
+    /// Entity holding the [`ShapeTrigger`].
+    pub trigger: Entity,
+    /// Entity that was detected by the trigger.
+    pub target: Entity,
+    /// Distance from the sector origin to the closest point of the target.
+    pub distance: f32,
+    /// Signed angle in radians between the forward axis of the shape and the closest point of the
+    /// target, counter-clockwise is positive. The forward axis of a sector is its center line.
+    pub angle: f32,
+    /// World space point of the target that is closest to the sector origin.
+    pub contact_point: Vec2,
+    pub parameters: SectorParameters,
+}
+
+pub trait TriggerMessage: Send + Sync + Message + From<TriggerContext> + 'static {}
+
+/// Shape trigger detection, messages are written by systems in this set.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TriggerDetectionSystems;
+
+/// Which of the detected entities a [`ShapeTrigger`] reports. Scores are normalized so that
+/// `0.0` is at the sector origin or center line and `1.0` at its radius or edge.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum TriggerSelection {
+    /// Every detected entity is reported.
+    #[default]
+    All,
+    /// Only the entity closest to the sector origin is reported.
+    ClosestDistance,
+    /// Only the entity closest to the center line of the sector is reported.
+    ClosestAngle,
+    /// Only the entity with the lowest weighted sum of distance and angle is reported.
+    Weighted {
+        distance_weight: f32,
+        angle_weight: f32,
+    },
+}
+
+impl TriggerSelection {
+    /// Score of a detection under this policy, lower is better. [`None`] if every detection is
+    /// selected.
+    pub fn score(&self, context: &TriggerContext) -> Option<f32> {
+        let distance = context.distance / context.parameters.radius.max(f32::EPSILON);
+        let angle = context.angle.abs() / (context.parameters.arc_angle / 2.0).max(f32::EPSILON);
+
+        match *self {
+            TriggerSelection::All => None,
+            TriggerSelection::ClosestDistance => Some(distance),
+            TriggerSelection::ClosestAngle => Some(angle),
+            TriggerSelection::Weighted {
+                distance_weight,
+                angle_weight,
+            } => Some(distance_weight * distance + angle_weight * angle),
+        }
+    }
+
+    /// Target of the detection with the best score. The `previous` target is kept as long as no
+    /// other detection beats its score by more than `hysteresis`. Equal scores go to the closer
+    /// target and then to the lower entity, so the result doesn't depend on iteration order.
+    /// [`TriggerSelection::All`] scores every detection the same, which selects the closest one.
+    pub fn select<'a>(
+        &self,
+        detections: impl IntoIterator<Item = &'a TriggerContext>,
+        previous: Option<Entity>,
+        hysteresis: f32,
+    ) -> Option<Entity> {
+        let score = |context: &TriggerContext| self.score(context).unwrap_or(0.0);
+
+        let mut best: Option<&TriggerContext> = None;
+        let mut previous_score = None;
+        for context in detections {
+            if Some(context.target) == previous {
+                previous_score = Some(score(context));
+            }
+            let is_better = best.is_none_or(|best| {
+                score(context)
+                    .total_cmp(&score(best))
+                    .then(context.distance.total_cmp(&best.distance))
+                    .then(context.target.cmp(&best.target))
+                    .is_lt()
+            });
+            if is_better {
+                best = Some(context);
+            }
+        }
+        let best = best?;
+
+        match (previous, previous_score) {
+            (Some(previous), Some(previous_score))
+                if previous_score <= score(best) + hysteresis =>
+            {
+                Some(previous)
+            }
+            _ => Some(best.target),
+        }
+    }
+}
+
+/// Detects entities inside of the [`TriggerShape`] on the same entity. The shape is queried once
+/// per update and the detections are then dispatched to every [`TriggerChannel`] of the trigger.
+///
+/// The query is skipped while neither the trigger, its shape nor any collider around it has moved
+/// since the last query, in which case the previous detections are kept.
 #[derive(Component)]
 #[require(Transform)]
-pub struct SectorTrigger<M> {
-    pub mesh: Mesh,
-    pub collider: Collider,
-    pub sector: Sector,
-    pub mask: LayerMask,
-    _message_type: PhantomData<M>,
+pub struct ShapeTrigger {
+    pub occlusion: Option<TriggerOcclusion>,
+    pub channels: Vec<TriggerChannel>,
+    /// Number of fixed ticks between two shape queries.
+    pub update_interval: u32,
+    /// Entities found by the last shape query along with their collision layer memberships.
+    candidates: EntityHashMap<(TriggerContext, LayerMask)>,
+    ticks_since_query: u32,
+    last_query: Option<QuerySnapshot>,
 }
 
-impl<M> SectorTrigger<M>
-where
-    M: SectorMessage,
-{
-    pub fn new(sector: Sector) -> Self {
+impl ShapeTrigger {
+    pub fn new() -> Self {
         Self {
-            mesh: sector.into_mesh(),
-            collider: sector.into_collider(),
-            sector,
-            mask: LayerMask::NONE,
-            _message_type: PhantomData::default(),
+            occlusion: None,
+            channels: Vec::new(),
+            update_interval: 1,
+            candidates: EntityHashMap::default(),
+            // Queried on the first tick regardless of the interval
+            ticks_since_query: u32::MAX,
+            last_query: None,
         }
     }
 
-    pub fn into_bundle(self, meshes: &mut ResMut<Assets<Mesh>>) -> impl Bundle {
-        (
-            Mesh2d(meshes.add(self.mesh.clone())),
-            self.collider.clone(),
-            CollisionLayers::new(LayerMask(ObjectLayer::None.to_bits()), self.mask),
-            self,
-        )
+    /// Queries the shape only every `update_interval` fixed ticks. The messages of the channels
+    /// are still written every tick from the last query.
+    pub fn with_update_interval(self, update_interval: u32) -> Self {
+        Self {
+            update_interval: update_interval.max(1),
+            ..self
+        }
+    }
+
+    pub fn with_occlusion(self, occlusion: TriggerOcclusion) -> Self {
+        Self {
+            occlusion: Some(occlusion),
+            ..self
+        }
+    }
+
+    pub fn with_channel(mut self, channel: TriggerChannel) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    /// Layers of every channel combined, which is what the shape is queried against.
+    pub fn mask(&self) -> LayerMask {
+        self.channels
+            .iter()
+            .fold(LayerMask::NONE, |mask, channel| mask | channel.mask)
+    }
+
+    /// First channel of the trigger that writes messages of type `M`.
+    pub fn channel<M: TriggerMessage>(&self) -> Option<&TriggerChannel> {
+        self.channels
+            .iter()
+            .find(|channel| channel.message_type == TypeId::of::<M>())
+    }
+}
+
+impl Default for ShapeTrigger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Everything a shape query depends on, used to skip queries while nothing has moved.
+#[derive(PartialEq)]
+struct QuerySnapshot {
+    origin: Vec2,
+    angle: f32,
+    shape_changed: Tick,
+    mask: LayerMask,
+    occlusion_mask: Option<LayerMask>,
+    /// Poses and layers of every collider inside of the broad phase bounds, including obstacles.
+    poses: EntityHashMap<(Position, Rotation, CollisionLayers)>,
+}
+
+/// Time that the shape queries of all triggers may take per fixed tick. Once it is used up, the
+/// remaining triggers are queried on the next tick, starting with the ones that waited the longest.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ShapeQueryBudget(pub Option<Duration>);
+
+/// Number of shape queries all triggers may run per fixed tick, on top of the
+/// [`ShapeQueryBudget`]. The remaining triggers are queried on the next tick the same way.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ShapeQueryLimit(pub Option<usize>);
+
+/// Writes a message of type `M` for the entities detected by a [`ShapeTrigger`] that are on the
+/// layers of the channel.
+pub struct TriggerChannel {
+    pub mask: LayerMask,
+    /// Whether entities that stay inside of the shape produce a message on every update.
+    pub stay_messages: bool,
+    pub selection: TriggerSelection,
+    /// Amount by which another entity has to beat the score of the currently selected entity to
+    /// take over the selection, which stops it from flickering between similar candidates.
+    pub hysteresis: f32,
+    message_type: TypeId,
+    detected: EntityHashMap<TriggerContext>,
+}
+
+impl TriggerChannel {
+    pub fn new<M: TriggerMessage>(mask: LayerMask) -> Self {
+        Self {
+            mask,
+            stay_messages: false,
+            selection: TriggerSelection::All,
+            hysteresis: 0.0,
+            message_type: TypeId::of::<M>(),
+            detected: EntityHashMap::default(),
+        }
     }
 
-    pub fn with_sector(self, sector: Sector) -> Self {
-        Self { sector, ..self }
+    pub fn with_stay_messages(self, stay_messages: bool) -> Self {
+        Self {
+            stay_messages,
+            ..self
+        }
     }
 
-    pub fn with_collider(self, collider: Collider) -> Self {
-        Self { collider, ..self }
+    pub fn with_selection(self, selection: TriggerSelection, hysteresis: f32) -> Self {
+        Self {
+            selection,
+            hysteresis,
+            ..self
+        }
     }
 
-    pub fn with_mask(self, mask: LayerMask) -> Self {
-        Self { mask, ..self }
+    /// Entities detected by the channel on its last update, along with how they were detected.
+    pub fn detected(&self) -> &EntityHashMap<TriggerContext> {
+        &self.detected
     }
 }
 
-pub struct SectorPlugin<M> {
+/// Registers the message type `M` and dispatches the detections of every [`ShapeTrigger`] to its
+/// channels of that type.
+pub struct TriggerPlugin<M> {
     _message_type: PhantomData<M>,
 }
 
-impl<M> Plugin for SectorPlugin<M>
+impl<M> Plugin for TriggerPlugin<M>
 where
-    M: SectorMessage,
+    M: TriggerMessage,
 {
     fn build(&self, app: &mut App) {
-        app.add_systems(FixedUpdate, sector_trigger_detection::<M>);
+        // Shared between the plugins of every message type
+        if !app.is_plugin_added::<TriggerShapePlugin>() {
+            app.add_plugins(TriggerShapePlugin);
+        }
+
+        app.add_message::<M>().add_systems(
+            FixedUpdate,
+            dispatch_trigger_messages::<M>
+                .in_set(TriggerDetectionSystems)
+                .after(ShapeQuerySystems),
+        );
     }
 }
 
-impl<M> Default for SectorPlugin<M> {
+impl<M> Default for TriggerPlugin<M> {
     fn default() -> Self {
         Self {
-            _message_type: PhantomData::default(),
+            _message_type: PhantomData,
         }
     }
 }
 
-fn sector_trigger_detection<M>(
+/// Queries the shape of every trigger and keeps the meshes and colliders of the shapes in sync
+/// with their parameters.
+pub struct TriggerShapePlugin;
+
+impl Plugin for TriggerShapePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShapeQueryBudget>()
+            .init_resource::<ShapeQueryLimit>()
+            .add_observer(reject_extra_trigger_shapes::<Sector>)
+            .add_observer(reject_extra_trigger_shapes::<AnnulusShape>)
+            .add_observer(reject_extra_trigger_shapes::<RectangleShape>)
+            .add_observer(reject_extra_trigger_shapes::<CapsuleShape>)
+            .add_observer(reject_extra_trigger_shapes::<PolygonShape>)
+            .configure_sets(
+                FixedUpdate,
+                ShapeQuerySystems.in_set(TriggerDetectionSystems),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    (
+                        sync_trigger_shapes::<Sector>,
+                        sync_trigger_shapes::<AnnulusShape>,
+                        sync_trigger_shapes::<RectangleShape>,
+                        sync_trigger_shapes::<CapsuleShape>,
+                        sync_trigger_shapes::<PolygonShape>,
+                    )
+                        .before(TriggerDetectionSystems),
+                    shape_trigger_query.in_set(ShapeQuerySystems),
+                ),
+            );
+    }
+}
+
+/// Shape queries of the triggers, which run before the detections are dispatched to channels.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct ShapeQuerySystems;
+
+/// Tests whether there is an unobstructed line between `origin` and `target`, ignoring the
+/// target entity itself.
+fn has_line_of_sight(
+    spatial_query: &SpatialQueryPipeline,
+    origin: Vec2,
+    target: Vec2,
+    target_entity: Entity,
+    obstacles: &SpatialQueryFilter,
+) -> bool {
+    let offset = target - origin;
+    let Ok(direction) = Dir2::new(offset) else {
+        return true;
+    };
+
+    spatial_query
+        .cast_ray_predicate(
+            origin,
+            direction,
+            offset.length(),
+            true,
+            obstacles,
+            &|entity| entity != target_entity,
+        )
+        .is_none()
+}
+
+/// Points on the target collider that are checked for line of sight: the closest point to the
+/// origin, the center and the closest points to each corner of its bounding box. Only points
+/// inside of the sector are used, falling back to the closest point if none of them are.
+fn visibility_samples(
+    shape: &impl TriggerShape,
+    origin: Vec2,
+    angle: f32,
+    (collider, position, rotation, aabb): (&Collider, &Position, &Rotation, &ColliderAabb),
+) -> Vec<Vec2> {
+    let project = |point: Vec2| collider.project_point(*position, *rotation, point, true).0;
+    let closest_point = project(origin);
+
+    let mut samples: Vec<Vec2> = [
+        closest_point,
+        position.0,
+        project(aabb.min),
+        project(aabb.max),
+        project(vec2(aabb.min.x, aabb.max.y)),
+        project(vec2(aabb.max.x, aabb.min.y)),
+    ]
+    .into_iter()
+    .filter(|sample| shape.contains_point(rotate_vec2(*sample - origin, -angle)))
+    .collect();
+
+    if samples.is_empty() {
+        samples.push(closest_point);
+    }
+    samples
+}
+
+/// Reduces the detections down to the one chosen by the selection policy of the channel.
+fn select_detection(channel: &TriggerChannel, detected: &mut EntityHashMap<TriggerContext>) {
+    if matches!(channel.selection, TriggerSelection::All) {
+        return;
+    }
+
+    let previous = channel
+        .detected
+        .keys()
+        .find(|entity| detected.contains_key(*entity))
+        .copied();
+    let selected = channel
+        .selection
+        .select(detected.values(), previous, channel.hysteresis);
+
+    detected.retain(|entity, _| Some(*entity) == selected);
+}
+
+type TargetQueryData = (
+    &'static Collider,
+    &'static Position,
+    &'static Rotation,
+    &'static ColliderAabb,
+    Option<&'static CollisionLayers>,
+);
+
+/// Shapes of the triggers with their colliders, one query per shape type.
+#[derive(SystemParam)]
+struct TriggerShapes<'w, 's> {
+    sectors: Query<'w, 's, (Ref<'static, Sector>, Option<&'static TriggerCollider>)>,
+    annuli: Query<'w, 's, (Ref<'static, AnnulusShape>, Option<&'static TriggerCollider>)>,
+    rectangles: Query<
+        'w,
+        's,
+        (
+            Ref<'static, RectangleShape>,
+            Option<&'static TriggerCollider>,
+        ),
+    >,
+    capsules: Query<'w, 's, (Ref<'static, CapsuleShape>, Option<&'static TriggerCollider>)>,
+    polygons: Query<'w, 's, (Ref<'static, PolygonShape>, Option<&'static TriggerCollider>)>,
+}
+
+type HasTriggerShape = Or<(
+    With<Sector>,
+    With<AnnulusShape>,
+    With<RectangleShape>,
+    With<CapsuleShape>,
+    With<PolygonShape>,
+)>;
+
+/// Queries the shapes of the triggers that are due, regardless of their shape type, within the
+/// [`ShapeQueryBudget`] and [`ShapeQueryLimit`].
+#[allow(clippy::type_complexity)]
+fn shape_trigger_query(
     spatial_query: Res<SpatialQueryPipeline>,
-    triggers: Query<(&SectorTrigger<M>, &GlobalTransform)>,
+    targets: Query<TargetQueryData>,
+    mut triggers: Query<
+        (Entity, &mut ShapeTrigger, &GlobalTransform, Option<&Mesh2d>),
+        HasTriggerShape,
+    >,
+    shapes: TriggerShapes,
+    budget: Res<ShapeQueryBudget>,
+    limit: Res<ShapeQueryLimit>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let mut due_triggers = Vec::new();
+    for (entity, mut trigger, ..) in &mut triggers {
+        trigger.ticks_since_query = trigger.ticks_since_query.saturating_add(1);
+        if trigger.ticks_since_query >= trigger.update_interval {
+            due_triggers.push((entity, trigger.ticks_since_query));
+        }
+    }
+    // Triggers that waited the longest go first so that none of them starve when the budget is
+    // used up every tick
+    due_triggers.sort_unstable_by_key(|(entity, ticks)| (Reverse(*ticks), *entity));
+
+    let mut query_time = Duration::ZERO;
+    for (query_count, (trigger_entity, _)) in due_triggers.into_iter().enumerate() {
+        if budget.is_some_and(|budget| query_time >= budget)
+            || limit.is_some_and(|limit| query_count >= limit)
+        {
+            break;
+        }
+        let start = Instant::now();
+
+        let Ok((_, mut trigger, global_transform, mesh)) = triggers.get_mut(trigger_entity) else {
+            continue;
+        };
+
+        // The first query spreads triggers with the same interval over different ticks
+        trigger.ticks_since_query = match trigger.last_query {
+            Some(_) => 0,
+            None => trigger_entity.index_u32() % trigger.update_interval,
+        };
+
+        let query = ShapeQuery {
+            spatial_query: &spatial_query,
+            targets: &targets,
+            trigger_entity,
+            trigger: &mut trigger,
+            global_transform,
+            mesh,
+            meshes: &mut meshes,
+        };
+        if let Ok(shape) = shapes.sectors.get(trigger_entity) {
+            query.run(shape);
+        } else if let Ok(shape) = shapes.annuli.get(trigger_entity) {
+            query.run(shape);
+        } else if let Ok(shape) = shapes.rectangles.get(trigger_entity) {
+            query.run(shape);
+        } else if let Ok(shape) = shapes.capsules.get(trigger_entity) {
+            query.run(shape);
+        } else if let Ok(shape) = shapes.polygons.get(trigger_entity) {
+            query.run(shape);
+        }
+
+        query_time += start.elapsed();
+    }
+}
+
+/// Shape query of a single trigger.
+struct ShapeQuery<'a, 'w, 's> {
+    spatial_query: &'a SpatialQueryPipeline,
+    targets: &'a Query<'w, 's, TargetQueryData>,
+    trigger_entity: Entity,
+    trigger: &'a mut ShapeTrigger,
+    global_transform: &'a GlobalTransform,
+    mesh: Option<&'a Mesh2d>,
+    meshes: &'a mut Assets<Mesh>,
+}
+
+impl ShapeQuery<'_, '_, '_> {
+    /// Stores the targets overlapping the shape as the candidates of the trigger, unless nothing
+    /// changed since its last query.
+    fn run<S: TriggerShape>(self, (shape, shape_collider): (Ref<S>, Option<&TriggerCollider>)) {
+        let ShapeQuery {
+            spatial_query,
+            targets,
+            trigger_entity,
+            trigger,
+            global_transform,
+            mesh,
+            meshes,
+        } = self;
+
+        let (_, rotation, translation) = global_transform.to_scale_rotation_translation();
+        let rotation_angle = rotation.to_euler(EulerRot::XYZ).2;
+        let origin = translation.xy();
+        let parameters = shape.parameters();
+        let mask = trigger.mask();
+
+        // Broad phase against the bounding box of the full circle, the exact test against the
+        // shape happens per candidate below
+        let bounds = ColliderAabb::new(origin, Vec2::splat(shape.bounding_radius()));
+        let nearby_entities = spatial_query.aabb_intersections_with_aabb(bounds);
+
+        let snapshot = QuerySnapshot {
+            origin,
+            angle: rotation_angle,
+            shape_changed: shape.last_changed(),
+            mask,
+            occlusion_mask: trigger.occlusion.as_ref().map(|occlusion| occlusion.mask),
+            poses: nearby_entities
+                .iter()
+                .filter_map(|entity| {
+                    let (_, position, rotation, _, layers) = targets.get(*entity).ok()?;
+                    Some((
+                        *entity,
+                        (*position, *rotation, layers.copied().unwrap_or_default()),
+                    ))
+                })
+                .collect(),
+        };
+        if trigger.last_query.as_ref() == Some(&snapshot) {
+            return;
+        }
+        trigger.last_query = Some(snapshot);
+
+        let mut candidates = EntityHashMap::default();
+        for entity in nearby_entities {
+            let Ok((collider, position, rotation, aabb, layers)) = targets.get(entity) else {
+                continue;
+            };
+
+            let memberships = layers.copied().unwrap_or_default().memberships;
+            if memberships & mask == LayerMask::NONE {
+                continue;
+            }
+
+            let Some(contact_point) = shape.intersect_collider(
+                shape_collider.map(|shape_collider| &shape_collider.0),
+                origin,
+                rotation_angle,
+                (collider, position, rotation),
+            ) else {
+                continue;
+            };
+
+            if let Some(occlusion) = &trigger.occlusion {
+                let obstacles = SpatialQueryFilter::from_mask(occlusion.mask);
+                let is_visible = visibility_samples(
+                    &*shape,
+                    origin,
+                    rotation_angle,
+                    (collider, position, rotation, aabb),
+                )
+                .into_iter()
+                .any(|sample| has_line_of_sight(spatial_query, origin, sample, entity, &obstacles));
+
+                if !is_visible {
+                    continue;
+                }
+            }
+
+            let to_contact = contact_point - origin;
+
+            candidates.insert(
+                entity,
+                (
+                    TriggerContext {
+                        transition: TriggerTransition::Enter,
+                        trigger: trigger_entity,
+                        target: entity,
+                        distance: to_contact.length(),
+                        angle: if to_contact == Vec2::ZERO {
+                            0.0
+                        } else {
+                            rotate_vec2(to_contact, -(rotation_angle + shape.forward_angle()))
+                                .to_angle()
+                        },
+                        contact_point,
+                        parameters,
+                    },
+                    memberships,
+                ),
+            );
+        }
+
+        trigger.candidates = candidates;
+
+        if let (Some(occlusion), Some(mesh)) = (&trigger.occlusion, mesh)
+            && occlusion.clip_mesh
+        {
+            let obstacles = SpatialQueryFilter::from_mask(occlusion.mask);
+            let rotation = Rot2::radians(rotation_angle);
+            let clipped_mesh = shape.to_visibility_mesh(|direction, max_distance| {
+                spatial_query
+                    .cast_ray(origin, rotation * direction, max_distance, true, &obstacles)
+                    .map_or(max_distance, |hit| hit.distance)
+            });
+
+            // Replacing the asset in place keeps the handle on the trigger valid
+            if let Some(clipped_mesh) = clipped_mesh {
+                let _ = meshes.insert(&mesh.0, clipped_mesh);
+            }
+        }
+    }
+}
+
+/// Writes the messages of every channel of type `M` from the candidates of the last shape query.
+fn dispatch_trigger_messages<M>(
+    mut triggers: Query<&mut ShapeTrigger>,
     mut message_writer: MessageWriter<M>,
 ) where
-    M: Message + From<Entity>,
+    M: TriggerMessage,
 {
-    for (trigger, global_transform) in &triggers {
-        let filter = SpatialQueryFilter::from_mask(trigger.mask);
+    for mut trigger in &mut triggers {
+        let ShapeTrigger {
+            channels,
+            candidates,
+            ..
+        } = &mut *trigger;
 
-        let (_, rotation, translation) = global_transform.to_scale_rotation_translation();
-        let rotation_angle = rotation.to_euler(EulerRot::XYZ).2;
+        for channel in channels
+            .iter_mut()
+            .filter(|channel| channel.message_type == TypeId::of::<M>())
+        {
+            let mut detected: EntityHashMap<TriggerContext> = candidates
+                .iter()
+                .filter(|(_, (_, memberships))| *memberships & channel.mask != LayerMask::NONE)
+                .map(|(entity, (context, _))| (*entity, *context))
+                .collect();
+
+            select_detection(channel, &mut detected);
+
+            for (entity, context) in detected.iter_mut() {
+                if !channel.detected.contains_key(entity) {
+                    message_writer.write(M::from(*context));
+                } else if channel.stay_messages {
+                    context.transition = TriggerTransition::Stay;
+                    message_writer.write(M::from(*context));
+                }
+            }
+            // Exits reuse the last known context since the target may not exist anymore
+            for (entity, context) in channel.detected.iter() {
+                if !detected.contains_key(entity) {
+                    message_writer.write(M::from(TriggerContext {
+                        transition: TriggerTransition::Exit,
+                        ..*context
+                    }));
+                }
+            }
+            channel.detected = detected;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::RunSystemOnce, mesh::VertexAttributeValues};
+
+    use super::*;
+    use crate::test_utils::{assert_close, context};
+
+    fn mesh_positions(mesh: &Mesh) -> Vec<Vec2> {
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("Meshes of trigger shapes have 3D float positions");
+        };
+        positions.iter().map(|[x, y, _]| vec2(*x, *y)).collect()
+    }
+
+    fn entity_context(target: u32, distance: f32, angle: f32) -> TriggerContext {
+        context(Entity::from_raw_u32(target).unwrap(), distance, angle)
+    }
+
+    #[test]
+    fn selection_scores_are_normalized_by_the_sector() {
+        let detection = entity_context(1, 50.0, -PI / 8.0);
+
+        assert_eq!(TriggerSelection::All.score(&detection), None);
+        assert_close(
+            TriggerSelection::ClosestDistance.score(&detection).unwrap(),
+            0.5,
+        );
+        assert_close(
+            TriggerSelection::ClosestAngle.score(&detection).unwrap(),
+            0.5,
+        );
+        assert_close(
+            TriggerSelection::Weighted {
+                distance_weight: 2.0,
+                angle_weight: 0.5,
+            }
+            .score(&detection)
+            .unwrap(),
+            1.25,
+        );
+    }
+
+    #[test]
+    fn selection_picks_the_lowest_score() {
+        let detections = [entity_context(1, 80.0, 0.0), entity_context(2, 20.0, 0.6)];
+
+        let closest = TriggerSelection::ClosestDistance.select(&detections, None, 0.0);
+        let centered = TriggerSelection::ClosestAngle.select(&detections, None, 0.0);
+
+        assert_eq!(closest, Some(detections[1].target));
+        assert_eq!(centered, Some(detections[0].target));
+    }
+
+    #[test]
+    fn selection_keeps_the_previous_target_within_the_hysteresis() {
+        let detections = [entity_context(1, 50.0, 0.0), entity_context(2, 45.0, 0.0)];
+        let previous = Some(detections[0].target);
+
+        let kept = TriggerSelection::ClosestDistance.select(&detections, previous, 0.1);
+        let taken_over = TriggerSelection::ClosestDistance.select(&detections, previous, 0.01);
+
+        assert_eq!(kept, previous);
+        assert_eq!(taken_over, Some(detections[1].target));
+    }
+
+    #[test]
+    fn selection_ignores_previous_targets_that_are_not_detected() {
+        let detections = [entity_context(1, 50.0, 0.0)];
+        let previous = Some(Entity::from_raw_u32(2).unwrap());
+
+        let selected = TriggerSelection::ClosestDistance.select(&detections, previous, 1.0);
+
+        assert_eq!(selected, Some(detections[0].target));
+    }
+
+    #[test]
+    fn selection_breaks_ties_by_distance_and_then_entity() {
+        let by_distance = [entity_context(1, 60.0, 0.2), entity_context(2, 40.0, -0.2)];
+        let by_entity = [entity_context(3, 40.0, 0.2), entity_context(4, 40.0, -0.2)];
+        let lower_entity = by_entity.iter().map(|context| context.target).min();
+
+        for (detections, expected) in [
+            (by_distance, Some(by_distance[1].target)),
+            (by_entity, lower_entity),
+        ] {
+            let mut reversed = detections;
+            reversed.reverse();
+
+            let selected = TriggerSelection::ClosestAngle.select(&detections, None, 0.0);
+            let reversed = TriggerSelection::ClosestAngle.select(&reversed, None, 0.0);
+
+            assert_eq!(selected, expected);
+            assert_eq!(reversed, expected);
+        }
+    }
+
+    #[test]
+    fn selecting_all_picks_the_closest_detection() {
+        let detections = [entity_context(1, 60.0, 0.0), entity_context(2, 40.0, 0.6)];
 
-        for entity in spatial_query.shape_intersections(
-            &trigger.collider,
-            translation.xy(),
-            rotation_angle,
-            &filter,
-        ) {
-            message_writer.write(M::from(entity));
+        let selected = TriggerSelection::All.select(&detections, None, 0.0);
+
+        assert_eq!(selected, Some(detections[1].target));
+    }
+
+    /// Overlap of a sector with a circle of radius 5 at `center`.
+    #[derive(Message)]
+    struct TestMessage(TriggerContext);
+
+    impl From<TriggerContext> for TestMessage {
+        fn from(context: TriggerContext) -> Self {
+            Self(context)
+        }
+    }
+
+    impl TriggerMessage for TestMessage {}
+
+    /// Targets and transitions of the messages read in the last update.
+    #[derive(Resource, Default)]
+    struct ReceivedMessages(Vec<(TriggerTransition, Entity)>);
+
+    fn receive_messages(
+        mut messages: MessageReader<TestMessage>,
+        mut received: ResMut<ReceivedMessages>,
+    ) {
+        received.0 = messages
+            .read()
+            .map(|TestMessage(context)| (context.transition, context.target))
+            .collect();
+    }
+
+    /// App dispatching the detections of a single trigger with the given channel.
+    fn dispatch_app(channel: TriggerChannel) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_message::<TestMessage>()
+            .init_resource::<ReceivedMessages>()
+            .add_systems(
+                Update,
+                (dispatch_trigger_messages::<TestMessage>, receive_messages).chain(),
+            );
+        let trigger = app
+            .world_mut()
+            .spawn(ShapeTrigger::new().with_channel(channel))
+            .id();
+        (app, trigger)
+    }
+
+    /// Updates the app as if the last shape query of the trigger found `detections`.
+    fn dispatch(
+        app: &mut App,
+        trigger: Entity,
+        detections: &[(TriggerContext, LayerMask)],
+    ) -> Vec<(TriggerTransition, Entity)> {
+        app.world_mut()
+            .get_mut::<ShapeTrigger>(trigger)
+            .unwrap()
+            .candidates = detections
+            .iter()
+            .map(|(context, memberships)| (context.target, (*context, *memberships)))
+            .collect();
+        app.update();
+        std::mem::take(&mut app.world_mut().resource_mut::<ReceivedMessages>().0)
+    }
+
+    #[test]
+    fn channels_send_enter_stay_and_exit_messages() {
+        let (mut app, trigger) = dispatch_app(
+            TriggerChannel::new::<TestMessage>(LayerMask::ALL).with_stay_messages(true),
+        );
+        let detection = entity_context(1, 50.0, 0.0);
+        let target = detection.target;
+
+        let detections = [(detection, LayerMask::DEFAULT)];
+        assert_eq!(
+            dispatch(&mut app, trigger, &detections),
+            [(TriggerTransition::Enter, target)]
+        );
+        assert_eq!(
+            dispatch(&mut app, trigger, &detections),
+            [(TriggerTransition::Stay, target)]
+        );
+        assert_eq!(
+            dispatch(&mut app, trigger, &[]),
+            [(TriggerTransition::Exit, target)]
+        );
+        assert_eq!(dispatch(&mut app, trigger, &[]), []);
+    }
+
+    #[test]
+    fn channels_without_stay_messages_only_send_enter_and_exit_messages() {
+        let (mut app, trigger) = dispatch_app(
+            TriggerChannel::new::<TestMessage>(LayerMask::ALL).with_stay_messages(false),
+        );
+        let detection = entity_context(1, 50.0, 0.0);
+        let target = detection.target;
+
+        let detections = [(detection, LayerMask::DEFAULT)];
+        assert_eq!(
+            dispatch(&mut app, trigger, &detections),
+            [(TriggerTransition::Enter, target)]
+        );
+        assert_eq!(dispatch(&mut app, trigger, &detections), []);
+        assert_eq!(
+            dispatch(&mut app, trigger, &[]),
+            [(TriggerTransition::Exit, target)]
+        );
+    }
+
+    #[test]
+    fn channels_only_send_messages_for_targets_on_their_layers() {
+        let (mut app, trigger) = dispatch_app(TriggerChannel::new::<TestMessage>(LayerMask(0b01)));
+        let on_layer = entity_context(1, 50.0, 0.0);
+        let off_layer = entity_context(2, 40.0, 0.0);
+
+        assert_eq!(
+            dispatch(
+                &mut app,
+                trigger,
+                &[(on_layer, LayerMask(0b11)), (off_layer, LayerMask(0b10))]
+            ),
+            [(TriggerTransition::Enter, on_layer.target)]
+        );
+        assert_eq!(
+            app.world()
+                .get::<ShapeTrigger>(trigger)
+                .unwrap()
+                .channel::<TestMessage>()
+                .unwrap()
+                .detected()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn channels_with_a_selection_only_message_the_selected_target() {
+        let (mut app, trigger) = dispatch_app(
+            TriggerChannel::new::<TestMessage>(LayerMask::ALL)
+                .with_selection(TriggerSelection::ClosestDistance, 0.1),
+        );
+        let [first, second] = [1, 2].map(|index| Entity::from_raw_u32(index).unwrap());
+        let detections = |first_distance: f32, second_distance: f32| {
+            [
+                (context(first, first_distance, 0.0), LayerMask::DEFAULT),
+                (context(second, second_distance, 0.0), LayerMask::DEFAULT),
+            ]
+        };
+
+        assert_eq!(
+            dispatch(&mut app, trigger, &detections(50.0, 60.0)),
+            [(TriggerTransition::Enter, first)]
+        );
+        // The second target is closer, but not by more than the hysteresis
+        assert_eq!(dispatch(&mut app, trigger, &detections(50.0, 45.0)), []);
+        assert_eq!(
+            dispatch(&mut app, trigger, &detections(50.0, 30.0)),
+            [
+                (TriggerTransition::Enter, second),
+                (TriggerTransition::Exit, first)
+            ]
+        );
+    }
+
+    fn intersect_circle(sector: &Sector, angle: f32, center: Vec2) -> Option<Vec2> {
+        sector.intersect_collider(
+            None,
+            Vec2::ZERO,
+            angle,
+            (
+                &Collider::circle(5.0),
+                &Position(center),
+                &Rotation::IDENTITY,
+            ),
+        )
+    }
+
+    #[test]
+    fn sector_intersects_colliders_inside_of_the_arc_at_their_closest_point() {
+        let sector = Sector::new(100.0, PI / 2.0, 0.0, 8.0);
+
+        let contact_point = intersect_circle(&sector, 0.0, vec2(50.0, 0.0)).unwrap();
+
+        assert!(
+            contact_point.distance(vec2(45.0, 0.0)) < 1e-3,
+            "{contact_point}"
+        );
+    }
+
+    #[test]
+    fn sector_misses_colliders_beyond_the_radius_or_behind_it() {
+        let sector = Sector::new(100.0, PI / 2.0, 0.0, 8.0);
+
+        assert_eq!(intersect_circle(&sector, 0.0, vec2(110.0, 0.0)), None);
+        assert_eq!(intersect_circle(&sector, 0.0, vec2(-50.0, 0.0)), None);
+    }
+
+    #[test]
+    fn sector_intersects_colliders_reaching_into_the_radius() {
+        let sector = Sector::new(100.0, PI / 2.0, 0.0, 8.0);
+
+        assert!(intersect_circle(&sector, 0.0, vec2(104.0, 0.0)).is_some());
+    }
+
+    #[test]
+    fn sector_intersects_colliders_crossing_an_edge_with_their_closest_point_outside() {
+        let sector = Sector::new(100.0, PI / 2.0, 0.0, 8.0);
+        // A wall above the origin whose closest point is straight up and outside of the arc, but
+        // which reaches across the upper edge of the sector
+        let wall = Collider::rectangle(200.0, 10.0);
+        let contact_point = sector.intersect_collider(
+            None,
+            Vec2::ZERO,
+            0.0,
+            (&wall, &Position(vec2(-50.0, 40.0)), &Rotation::IDENTITY),
+        );
+
+        assert!(contact_point.is_some());
+    }
+
+    #[test]
+    fn sector_intersection_follows_the_trigger_rotation() {
+        let sector = Sector::new(100.0, PI / 2.0, 0.0, 8.0);
+
+        assert!(intersect_circle(&sector, PI / 2.0, vec2(0.0, 50.0)).is_some());
+        assert_eq!(intersect_circle(&sector, PI / 2.0, vec2(50.0, 0.0)), None);
+    }
+
+    #[test]
+    fn visibility_mesh_without_obstacles_reaches_the_radius() {
+        let sector = Sector::new(100.0, PI / 2.0, 0.0, 8.0);
+        let mesh = sector
+            .to_visibility_mesh(|_, max_distance| max_distance)
+            .unwrap();
+
+        let positions = mesh_positions(&mesh);
+        assert_eq!(positions[0], Vec2::ZERO);
+        for point in &positions[1..] {
+            assert!((point.length() - 100.0).abs() < 1e-3, "{point}");
         }
     }
+
+    #[test]
+    fn visibility_mesh_is_cut_off_behind_obstacles_in_the_middle_of_the_arc() {
+        let sector = Sector::new(100.0, PI / 2.0, 0.0, 8.0);
+        // A narrow pillar 50 units in front of the sector, covering about 6 degrees
+        let mesh = sector
+            .to_visibility_mesh(|direction, max_distance| {
+                if direction.to_angle().abs() < 0.05 {
+                    50.0
+                } else {
+                    max_distance
+                }
+            })
+            .unwrap();
+
+        let positions = mesh_positions(&mesh);
+        let (blocked, visible): (Vec<Vec2>, Vec<Vec2>) = positions[1..]
+            .iter()
+            .partition(|point| point.to_angle().abs() < 0.05);
+
+        assert!(!blocked.is_empty(), "no ray hit the pillar");
+        assert!(
+            blocked
+                .iter()
+                .all(|point| (point.length() - 50.0).abs() < 1e-3)
+        );
+        assert!(
+            visible
+                .iter()
+                .all(|point| (point.length() - 100.0).abs() < 1e-3)
+        );
+        // Both edges of the arc are still visible next to the pillar
+        assert!(visible.iter().any(|point| point.to_angle() > 0.7));
+        assert!(visible.iter().any(|point| point.to_angle() < -0.7));
+    }
+
+    #[test]
+    fn visibility_mesh_samples_are_at_most_the_spacing_apart() {
+        let sector = Sector::new(200.0, PI, PI / 2.0, 1.0);
+        let mesh = sector
+            .to_visibility_mesh(|_, max_distance| max_distance)
+            .unwrap();
+
+        let positions = mesh_positions(&mesh);
+        for pair in positions[1..].windows(2) {
+            assert!(pair[0].distance(pair[1]) <= VISIBILITY_SAMPLE_SPACING + 1e-3);
+        }
+    }
+
+    #[test]
+    fn triggers_of_every_shape_type_share_one_query_queue() {
+        let mut world = World::new();
+        world.init_resource::<SpatialQueryPipeline>();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<ShapeQueryBudget>();
+        world.insert_resource(ShapeQueryLimit(Some(1)));
+
+        let sector = world
+            .spawn((
+                Sector::new(100.0, PI / 2.0, 0.0, 8.0),
+                ShapeTrigger::new(),
+                GlobalTransform::default(),
+            ))
+            .id();
+        let polygon = world
+            .spawn((
+                PolygonShape::new(vec![Vec2::ZERO, Vec2::X, Vec2::Y]),
+                ShapeTrigger::new(),
+                GlobalTransform::default(),
+            ))
+            .id();
+        let is_queried = |world: &World, entity: Entity| {
+            world
+                .get::<ShapeTrigger>(entity)
+                .unwrap()
+                .last_query
+                .is_some()
+        };
+
+        world.run_system_once(shape_trigger_query).unwrap();
+        // Only a single query fits into a tick
+        assert!(is_queried(&world, sector) != is_queried(&world, polygon));
+
+        // The trigger queried first is due again, but the other one waited longer
+        world.run_system_once(shape_trigger_query).unwrap();
+        assert!(is_queried(&world, sector) && is_queried(&world, polygon));
+    }
+
+    #[test]
+    fn stationary_targets_are_queried_again_when_their_layers_change() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<ShapeQueryBudget>();
+        world.init_resource::<ShapeQueryLimit>();
+
+        let trigger = world
+            .spawn((
+                Sector::new(100.0, PI / 2.0, 0.0, 8.0),
+                ShapeTrigger::new()
+                    .with_channel(TriggerChannel::new::<TestMessage>(LayerMask(0b10))),
+                GlobalTransform::default(),
+            ))
+            .id();
+        let target = world
+            .spawn((
+                Collider::circle(10.0),
+                CollisionLayers::new(0b01, LayerMask::ALL),
+                Position(vec2(50.0, 0.0)),
+                Rotation::default(),
+            ))
+            .id();
+
+        let mut colliders =
+            world.query::<(Entity, &Position, &Rotation, &Collider, &CollisionLayers)>();
+        let mut spatial_query = SpatialQueryPipeline::new();
+        spatial_query.update(colliders.iter(&world));
+        world.insert_resource(spatial_query);
+        let is_detected = |world: &World| {
+            world
+                .get::<ShapeTrigger>(trigger)
+                .unwrap()
+                .candidates
+                .contains_key(&target)
+        };
+
+        world.run_system_once(shape_trigger_query).unwrap();
+        assert!(!is_detected(&world));
+
+        world
+            .entity_mut(target)
+            .insert(CollisionLayers::new(0b10, LayerMask::ALL));
+        world.run_system_once(shape_trigger_query).unwrap();
+        assert!(is_detected(&world));
+    }
 }
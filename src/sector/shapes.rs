@@ -0,0 +1,689 @@
+use std::f32::consts::PI;
+
+use avian2d::{collision::collider::contact_query, prelude::*};
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+};
+
+use crate::{
+    math::rotate_vec2,
+    sector::{Sector, SectorParameters},
+};
+
+/// Shape of a [`ShapeTrigger`](super::ShapeTrigger), relative to the transform of its entity. A trigger uses exactly
+/// one shape component, whose parameters can be changed at runtime.
+pub trait TriggerShape: Component + Clone {
+    /// Radius of a circle around the trigger origin that contains the whole shape.
+    fn bounding_radius(&self) -> f32;
+
+    /// Tests whether a point relative to the trigger origin lies inside of the shape.
+    fn contains_point(&self, point: Vec2) -> bool;
+
+    /// Angle in radians relative to the trigger that the shape faces, which the angles of its
+    /// detections are measured from. Defaults to the facing of the trigger itself.
+    fn forward_angle(&self) -> f32 {
+        0.0
+    }
+
+    /// Parameters reported with every detection of the shape. Shapes other than a
+    /// [`Sector`](crate::sector::Sector) report their bounding circle centered on their forward
+    /// axis, which scores distances relative to the bounding radius and angles relative to the
+    /// forward axis.
+    fn parameters(&self) -> SectorParameters {
+        SectorParameters {
+            radius: self.bounding_radius(),
+            arc_angle: 2.0 * PI,
+            center_angle: self.forward_angle(),
+        }
+    }
+
+    fn to_mesh(&self) -> Mesh;
+
+    /// Collider covering the shape relative to the trigger origin, or [`None`] if the shape is
+    /// degenerate.
+    fn to_collider(&self) -> Option<Collider>;
+
+    /// Builds the part of the shape that can be seen from the trigger origin, which is a
+    /// visibility polygon sampled with one ray per direction rather than an exact one. Only shapes
+    /// that are a fan around the trigger origin support this, every other shape keeps its full
+    /// mesh.
+    ///
+    /// * `visible_distance` - Unobstructed distance from the origin along a direction relative to
+    ///   the trigger, up to the given maximum distance
+    fn to_visibility_mesh(&self, _visible_distance: impl Fn(Dir2, f32) -> f32) -> Option<Mesh> {
+        None
+    }
+
+    /// Overlap test between the shape and a collider, returning the point of the collider closest
+    /// to the trigger origin if they overlap. By default this is an intersection test against the
+    /// [`TriggerCollider`] of the shape.
+    ///
+    /// * `shape_collider` - Collider of the shape, see [`TriggerShape::to_collider`]
+    /// * `origin` - World space position of the trigger origin
+    /// * `angle` - World space rotation of the trigger in radians
+    /// * `collider` - Collider to test along with its position and rotation
+    fn intersect_collider(
+        &self,
+        shape_collider: Option<&Collider>,
+        origin: Vec2,
+        angle: f32,
+        (collider, position, rotation): (&Collider, &Position, &Rotation),
+    ) -> Option<Vec2> {
+        let is_intersecting = contact_query::intersection_test(
+            shape_collider?,
+            Position(origin),
+            Rotation::radians(angle),
+            collider,
+            *position,
+            *rotation,
+        )
+        .unwrap_or(false);
+
+        is_intersecting.then(|| collider.project_point(*position, *rotation, origin, true).0)
+    }
+
+    /// Creates the shape along with the mesh that displays it.
+    fn into_bundle(self, meshes: &mut ResMut<Assets<Mesh>>) -> impl Bundle {
+        (Mesh2d(meshes.add(self.to_mesh())), self)
+    }
+}
+
+/// Collider of the [`TriggerShape`] on the same entity, kept in sync with the shape. This is only
+/// used for queries and never takes part in the physics simulation.
+#[derive(Component, Deref, DerefMut)]
+pub struct TriggerCollider(pub Collider);
+
+/// Ring between two circles around the trigger origin, e.g. for "too close" and "in range"
+/// checks at once.
+#[derive(Component, Clone, Debug)]
+pub struct AnnulusShape {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub min_edges_per_radian: f32,
+}
+
+impl AnnulusShape {
+    pub fn new(inner_radius: f32, outer_radius: f32, min_edges_per_radian: f32) -> Self {
+        Self {
+            inner_radius,
+            outer_radius,
+            min_edges_per_radian,
+        }
+    }
+
+    /// Points along the inner circle followed by the points along the outer circle, along with the
+    /// triangles between them. Without an inner circle the shape is a fan of triangles around the
+    /// trigger origin instead, which is the first point.
+    fn triangles(&self) -> (Vec<Vec2>, Vec<u32>) {
+        let segment_count = ((2.0 * PI * self.min_edges_per_radian).ceil() as u32).max(3);
+        let step_angle = 2.0 * PI / segment_count as f32;
+        let inner_radius = self.inner_radius.clamp(0.0, self.outer_radius);
+
+        let unit_points = (0..segment_count).map(|i| rotate_vec2(Vec2::X, step_angle * i as f32));
+        let outer_points = unit_points.clone().map(|unit| unit * self.outer_radius);
+
+        if inner_radius <= 0.0 {
+            let points = std::iter::once(Vec2::ZERO).chain(outer_points).collect();
+            let indices = (0..segment_count)
+                .flat_map(|i| [0, i + 1, (i + 1) % segment_count + 1])
+                .collect();
+            return (points, indices);
+        }
+
+        let points = unit_points
+            .map(|unit| unit * inner_radius)
+            .chain(outer_points)
+            .collect();
+
+        let indices = (0..segment_count)
+            .flat_map(|i| {
+                let next = (i + 1) % segment_count;
+                let (inner, inner_next) = (i, next);
+                let (outer, outer_next) = (i + segment_count, next + segment_count);
+                [inner, outer, outer_next, inner, outer_next, inner_next]
+            })
+            .collect();
+
+        (points, indices)
+    }
+}
+
+impl TriggerShape for AnnulusShape {
+    fn bounding_radius(&self) -> f32 {
+        self.outer_radius
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        let distance_squared = point.length_squared();
+        distance_squared >= self.inner_radius * self.inner_radius
+            && distance_squared <= self.outer_radius * self.outer_radius
+    }
+
+    fn to_mesh(&self) -> Mesh {
+        let (points, indices) = self.triangles();
+        triangle_mesh(&points, indices)
+    }
+
+    /// The circles are approximated by the same edges as the mesh, except for annuli without an
+    /// inner circle that are a plain circle.
+    fn to_collider(&self) -> Option<Collider> {
+        if self.inner_radius <= 0.0 {
+            return (self.outer_radius > 0.0).then(|| Collider::circle(self.outer_radius));
+        }
+
+        let (points, indices) = self.triangles();
+        trimesh_collider(points, indices)
+    }
+}
+
+/// Rectangle that can be moved and rotated away from the trigger origin, e.g. for melee swings.
+#[derive(Component, Clone, Debug)]
+pub struct RectangleShape {
+    pub half_size: Vec2,
+    /// Center of the rectangle relative to the trigger origin.
+    pub center: Vec2,
+    /// Rotation of the rectangle around its center in radians.
+    pub angle: f32,
+}
+
+impl RectangleShape {
+    pub fn new(half_size: Vec2) -> Self {
+        Self {
+            half_size,
+            center: Vec2::ZERO,
+            angle: 0.0,
+        }
+    }
+
+    pub fn with_center(self, center: Vec2) -> Self {
+        Self { center, ..self }
+    }
+
+    pub fn with_angle(self, angle: f32) -> Self {
+        Self { angle, ..self }
+    }
+}
+
+impl TriggerShape for RectangleShape {
+    fn bounding_radius(&self) -> f32 {
+        self.center.length() + self.half_size.length()
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        let local = rotate_vec2(point - self.center, -self.angle);
+        local.abs().cmple(self.half_size).all()
+    }
+
+    /// Towards the center of the rectangle, or the facing of the trigger if it is centered on it.
+    fn forward_angle(&self) -> f32 {
+        if self.center == Vec2::ZERO {
+            0.0
+        } else {
+            self.center.to_angle()
+        }
+    }
+
+    fn to_mesh(&self) -> Mesh {
+        Mesh::from(Rectangle::from_size(self.half_size * 2.0)).transformed_by(
+            Transform::from_translation(self.center.extend(0.0))
+                .with_rotation(Quat::from_rotation_z(self.angle)),
+        )
+    }
+
+    fn to_collider(&self) -> Option<Collider> {
+        let size = self.half_size * 2.0;
+        Some(Collider::compound(vec![(
+            Position(self.center),
+            Rotation::radians(self.angle),
+            Collider::rectangle(size.x, size.y),
+        )]))
+    }
+}
+
+/// Line segment from `start` to `end` with rounded ends, e.g. for thrusts.
+#[derive(Component, Clone, Debug)]
+pub struct CapsuleShape {
+    /// Start of the segment relative to the trigger origin.
+    pub start: Vec2,
+    /// End of the segment relative to the trigger origin.
+    pub end: Vec2,
+    pub radius: f32,
+}
+
+impl CapsuleShape {
+    pub fn new(start: Vec2, end: Vec2, radius: f32) -> Self {
+        Self { start, end, radius }
+    }
+}
+
+impl TriggerShape for CapsuleShape {
+    fn bounding_radius(&self) -> f32 {
+        self.start.length().max(self.end.length()) + self.radius
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        let segment = self.end - self.start;
+        let t = (point - self.start).dot(segment) / segment.length_squared().max(f32::EPSILON);
+        let closest = self.start + segment * t.clamp(0.0, 1.0);
+        point.distance_squared(closest) <= self.radius * self.radius
+    }
+
+    /// Towards the middle of the segment, or the facing of the trigger if it is centered on it.
+    fn forward_angle(&self) -> f32 {
+        let middle = (self.start + self.end) / 2.0;
+        if middle == Vec2::ZERO {
+            0.0
+        } else {
+            middle.to_angle()
+        }
+    }
+
+    fn to_mesh(&self) -> Mesh {
+        let segment = self.end - self.start;
+        // The capsule primitive is vertical and centered around its origin
+        Mesh::from(Capsule2d::new(self.radius, segment.length())).transformed_by(
+            Transform::from_translation(((self.start + self.end) / 2.0).extend(0.0))
+                .with_rotation(Quat::from_rotation_z(segment.to_angle() - PI / 2.0)),
+        )
+    }
+
+    fn to_collider(&self) -> Option<Collider> {
+        Some(Collider::capsule_endpoints(
+            self.radius,
+            self.start,
+            self.end,
+        ))
+    }
+}
+
+/// Simple polygon which may be concave, e.g. for level zones.
+#[derive(Component, Clone, Debug)]
+pub struct PolygonShape {
+    /// Vertices relative to the trigger origin in either winding order.
+    pub vertices: Vec<Vec2>,
+}
+
+impl PolygonShape {
+    pub fn new(vertices: Vec<Vec2>) -> Self {
+        Self { vertices }
+    }
+}
+
+impl TriggerShape for PolygonShape {
+    fn bounding_radius(&self) -> f32 {
+        self.vertices
+            .iter()
+            .map(|vertex| vertex.length())
+            .fold(0.0, f32::max)
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        polygon_contains_point(&self.vertices, point)
+    }
+
+    fn to_mesh(&self) -> Mesh {
+        triangle_mesh(&self.vertices, triangulate(&self.vertices))
+    }
+
+    fn to_collider(&self) -> Option<Collider> {
+        trimesh_collider(self.vertices.clone(), triangulate(&self.vertices))
+    }
+}
+
+/// Tests whether a point lies inside of a simple polygon given by its vertices in either winding
+/// order.
+pub fn polygon_contains_point(vertices: &[Vec2], point: Vec2) -> bool {
+    // Even-odd rule, counting the edges crossed by a ray towards positive x
+    let mut is_inside = false;
+    for (i, a) in vertices.iter().enumerate() {
+        let b = vertices[(i + 1) % vertices.len()];
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            is_inside = !is_inside;
+        }
+    }
+    is_inside
+}
+
+/// Builds a mesh from a flat list of triangle indices into `points`.
+pub(super) fn triangle_mesh(points: &[Vec2], indices: Vec<u32>) -> Mesh {
+    let positions: Vec<Vec3> = points.iter().map(|point| point.extend(0.0)).collect();
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Builds a collider from a flat list of triangle indices into `points`, or [`None`] if there are
+/// no valid triangles.
+pub(super) fn trimesh_collider(points: Vec<Vec2>, indices: Vec<u32>) -> Option<Collider> {
+    let triangles = indices
+        .chunks_exact(3)
+        .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+        .collect();
+    Collider::try_trimesh(points, triangles).ok()
+}
+
+/// Splits a simple polygon into counter-clockwise triangles by clipping off one ear at a time.
+/// Self-intersecting polygons are only partially triangulated.
+fn triangulate(vertices: &[Vec2]) -> Vec<u32> {
+    let signed_area: f32 = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum();
+    let mut remaining: Vec<usize> = if signed_area >= 0.0 {
+        (0..vertices.len()).collect()
+    } else {
+        (0..vertices.len()).rev().collect()
+    };
+
+    let mut indices = Vec::new();
+    while remaining.len() >= 3 {
+        let count = remaining.len();
+        let corner = |i: usize| {
+            [
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            ]
+        };
+
+        let ear = (0..count).map(corner).find(|&[a, b, c]| {
+            let (pa, pb, pc) = (vertices[a], vertices[b], vertices[c]);
+            // An ear is a convex corner without any other vertex inside of it
+            (pb - pa).perp_dot(pc - pb) > 0.0
+                && remaining.iter().all(|&other| {
+                    [a, b, c].contains(&other)
+                        || (pb - pa).perp_dot(vertices[other] - pa) < 0.0
+                        || (pc - pb).perp_dot(vertices[other] - pb) < 0.0
+                        || (pa - pc).perp_dot(vertices[other] - pc) < 0.0
+                })
+        });
+        let Some([a, b, c]) = ear else {
+            break;
+        };
+
+        indices.extend([a as u32, b as u32, c as u32]);
+        remaining.retain(|&vertex| vertex != b);
+    }
+
+    indices
+}
+
+/// Makes sure that triggers have only one shape, since every shape would query and dispatch the
+/// detections of the trigger on its own and replace those of the others. Extra shapes are removed
+/// again, the first one of shapes added together is kept.
+pub(super) fn reject_extra_trigger_shapes<S: TriggerShape>(
+    add: On<Add, S>,
+    mut commands: Commands,
+) {
+    let entity = add.entity;
+
+    // Shapes added together are only all present once every observer ran
+    commands.queue(move |world: &mut World| {
+        let Ok(entity_ref) = world.get_entity(entity) else {
+            return;
+        };
+        let shape_count = [
+            entity_ref.contains::<Sector>(),
+            entity_ref.contains::<AnnulusShape>(),
+            entity_ref.contains::<RectangleShape>(),
+            entity_ref.contains::<CapsuleShape>(),
+            entity_ref.contains::<PolygonShape>(),
+        ]
+        .into_iter()
+        .filter(|has_shape| *has_shape)
+        .count();
+
+        if shape_count > 1 && entity_ref.contains::<S>() {
+            error!(
+                "{entity} already has a trigger shape, a trigger can only have one so its {} is \
+                 removed",
+                ShortName::of::<S>()
+            );
+            world.entity_mut(entity).remove::<S>();
+        }
+    });
+}
+
+/// Keeps the meshes and colliders of trigger shapes in sync with their parameters.
+pub(super) fn sync_trigger_shapes<S: TriggerShape>(
+    shapes: Query<(Entity, &S, Option<&Mesh2d>), Changed<S>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut commands: Commands,
+) {
+    for (entity, shape, mesh) in &shapes {
+        if let Some(mesh) = mesh {
+            // Replacing the asset in place keeps the handle on the entity valid
+            let _ = meshes.insert(&mesh.0, shape.to_mesh());
+        }
+
+        match shape.to_collider() {
+            Some(collider) => commands.entity(entity).insert(TriggerCollider(collider)),
+            None => commands.entity(entity).remove::<TriggerCollider>(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::mesh::VertexAttributeValues;
+
+    use super::*;
+
+    /// Signed area of every triangle, positive for counter-clockwise ones.
+    fn triangle_areas(vertices: &[Vec2], indices: &[u32]) -> Vec<f32> {
+        indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| vertices[triangle[i] as usize]);
+                (b - a).perp_dot(c - a) / 2.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn triangulate_covers_convex_polygons_in_either_winding() {
+        let square = vec![
+            vec2(0.0, 0.0),
+            vec2(2.0, 0.0),
+            vec2(2.0, 2.0),
+            vec2(0.0, 2.0),
+        ];
+        let mut clockwise = square.clone();
+        clockwise.reverse();
+
+        for vertices in [square, clockwise] {
+            let areas = triangle_areas(&vertices, &triangulate(&vertices));
+
+            assert_eq!(areas.len(), 2);
+            assert!(areas.iter().all(|area| *area > 0.0), "{areas:?}");
+            assert!((areas.iter().sum::<f32>() - 4.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn triangulate_covers_concave_polygons_without_leaving_them() {
+        // L shape whose reflex corner is at (1, 1)
+        let vertices = vec![
+            vec2(0.0, 0.0),
+            vec2(2.0, 0.0),
+            vec2(2.0, 1.0),
+            vec2(1.0, 1.0),
+            vec2(1.0, 2.0),
+            vec2(0.0, 2.0),
+        ];
+        let indices = triangulate(&vertices);
+        let areas = triangle_areas(&vertices, &indices);
+
+        assert_eq!(areas.len(), vertices.len() - 2);
+        assert!(areas.iter().all(|area| *area > 0.0), "{areas:?}");
+        assert!((areas.iter().sum::<f32>() - 3.0).abs() < 1e-5);
+        // Triangles of a simple polygon don't overlap, so covering its area means none of them
+        // reaches over the notch
+        let polygon = PolygonShape::new(vertices.clone());
+        for triangle in indices.chunks_exact(3) {
+            let centroid = triangle.iter().map(|i| vertices[*i as usize]).sum::<Vec2>() / 3.0;
+            assert!(polygon.contains_point(centroid), "{centroid}");
+        }
+    }
+
+    #[test]
+    fn triangulate_needs_at_least_three_vertices() {
+        assert!(triangulate(&[]).is_empty());
+        assert!(triangulate(&[Vec2::ZERO, Vec2::X]).is_empty());
+    }
+
+    fn shape_app() -> App {
+        let mut app = App::new();
+        app.add_observer(reject_extra_trigger_shapes::<Sector>)
+            .add_observer(reject_extra_trigger_shapes::<RectangleShape>);
+        app
+    }
+
+    #[test]
+    fn shapes_added_to_a_trigger_with_a_shape_are_removed() {
+        let mut app = shape_app();
+        let world = app.world_mut();
+
+        let entity = world.spawn(Sector::new(100.0, PI / 2.0, 0.0, 8.0)).id();
+        world.flush();
+        world
+            .entity_mut(entity)
+            .insert(RectangleShape::new(Vec2::ONE));
+        world.flush();
+
+        assert!(world.entity(entity).contains::<Sector>());
+        assert!(!world.entity(entity).contains::<RectangleShape>());
+    }
+
+    #[test]
+    fn triggers_spawned_with_several_shapes_keep_one() {
+        let mut app = shape_app();
+        let world = app.world_mut();
+
+        let entity = world
+            .spawn((
+                Sector::new(100.0, PI / 2.0, 0.0, 8.0),
+                RectangleShape::new(Vec2::ONE),
+            ))
+            .id();
+        world.flush();
+
+        let entity = world.entity(entity);
+        assert!(entity.contains::<Sector>() != entity.contains::<RectangleShape>());
+    }
+
+    #[test]
+    fn changed_shapes_rebuild_their_mesh_and_collider() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(sync_trigger_shapes::<RectangleShape>);
+
+        let shape = RectangleShape::new(vec2(10.0, 20.0));
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(shape.to_mesh());
+        let entity = world.spawn((shape, Mesh2d(mesh.clone()))).id();
+        schedule.run(&mut world);
+
+        world.get_mut::<RectangleShape>(entity).unwrap().half_size = vec2(30.0, 5.0);
+        schedule.run(&mut world);
+
+        let meshes = world.resource::<Assets<Mesh>>();
+        let Some(VertexAttributeValues::Float32x3(positions)) = meshes
+            .get(&mesh)
+            .unwrap()
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("Meshes of trigger shapes have 3D float positions");
+        };
+        let mesh_extent = positions
+            .iter()
+            .fold(Vec2::ZERO, |extent, [x, y, _]| extent.max(vec2(*x, *y)));
+        assert_eq!(mesh_extent, vec2(30.0, 5.0));
+
+        let collider = &world.get::<TriggerCollider>(entity).unwrap().0;
+        let aabb = collider.aabb(Vec2::ZERO, 0.0);
+        assert!(
+            (aabb.max - vec2(30.0, 5.0)).length() < 1e-3,
+            "{:?}",
+            aabb.max
+        );
+    }
+
+    #[test]
+    fn shapes_intersect_colliders_through_their_trigger_collider() {
+        let shape = RectangleShape::new(vec2(20.0, 10.0)).with_center(vec2(50.0, 0.0));
+        let shape_collider = shape.to_collider();
+        let intersect = |angle: f32, center: Vec2| {
+            shape.intersect_collider(
+                shape_collider.as_ref(),
+                Vec2::ZERO,
+                angle,
+                (
+                    &Collider::circle(5.0),
+                    &Position(center),
+                    &Rotation::IDENTITY,
+                ),
+            )
+        };
+
+        let contact_point = intersect(0.0, vec2(50.0, 0.0)).unwrap();
+        assert!(
+            contact_point.distance(vec2(45.0, 0.0)) < 1e-3,
+            "{contact_point}"
+        );
+        assert_eq!(intersect(0.0, vec2(50.0, 30.0)), None);
+        // The rectangle is rotated along with the trigger
+        assert!(intersect(PI / 2.0, vec2(0.0, 50.0)).is_some());
+        assert_eq!(intersect(PI / 2.0, vec2(50.0, 0.0)), None);
+    }
+
+    #[test]
+    fn annuli_without_an_inner_circle_are_circles() {
+        let shape = AnnulusShape::new(0.0, 50.0, 1.0);
+        let (vertices, indices) = shape.triangles();
+        let areas = triangle_areas(&vertices, &indices);
+        assert!(areas.iter().all(|area| *area > 0.0), "{areas:?}");
+
+        let shape_collider = shape.to_collider();
+        let intersect = |center: Vec2| {
+            shape.intersect_collider(
+                shape_collider.as_ref(),
+                Vec2::ZERO,
+                0.0,
+                (
+                    &Collider::circle(5.0),
+                    &Position(center),
+                    &Rotation::IDENTITY,
+                ),
+            )
+        };
+
+        assert_eq!(intersect(Vec2::ZERO), Some(Vec2::ZERO));
+        assert!(intersect(vec2(30.0, -30.0)).is_some());
+        assert_eq!(intersect(vec2(60.0, 0.0)), None);
+    }
+
+    #[test]
+    fn shapes_without_a_trigger_collider_intersect_nothing() {
+        let shape = RectangleShape::new(vec2(20.0, 10.0));
+
+        let contact_point = shape.intersect_collider(
+            None,
+            Vec2::ZERO,
+            0.0,
+            (
+                &Collider::circle(5.0),
+                &Position::default(),
+                &Rotation::IDENTITY,
+            ),
+        );
+
+        assert_eq!(contact_point, None);
+    }
+}
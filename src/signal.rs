@@ -0,0 +1,152 @@
+//! Boolean signals sent over named channels. Emitters like levers and pressure plates drive the
+//! channels, gates combine them into new channels, and receivers like doors react to them.
+
+mod emitters;
+mod gates;
+mod wiring;
+
+pub use emitters::*;
+pub use gates::*;
+pub use wiring::*;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    interaction::InteractionSystems,
+    persistence::PersistenceAppExt,
+    sector::{TriggerDetectionSystems, TriggerPlugin},
+};
+
+pub struct SignalPlugin;
+
+impl Plugin for SignalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(TriggerPlugin::<PressurePlateMessage>::default())
+            .init_resource::<Signals>()
+            .init_asset::<Wiring>()
+            .init_asset_loader::<WiringLoader>()
+            .register_persistent::<Lever>()
+            .add_systems(
+                Update,
+                (
+                    (toggle_levers, press_buttons).after(InteractionSystems),
+                    spawn_level_wiring,
+                    update_emitter_colors,
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    (update_pressure_plates, update_buttons, update_signal_timers),
+                    collect_signals,
+                    evaluate_gates,
+                    update_receivers,
+                )
+                    .chain()
+                    .in_set(SignalSystems)
+                    .after(TriggerDetectionSystems),
+            );
+    }
+}
+
+/// Signal propagation, [`SignalReceiver`]s are up to date after this set.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SignalSystems;
+
+/// Current value of every channel, a channel is on while any of its emitters is on. Channels
+/// without emitters are off.
+#[derive(Resource, Default, Debug)]
+pub struct Signals(HashMap<String, bool>);
+
+impl Signals {
+    pub fn is_on(&self, channel: &str) -> bool {
+        self.0.get(channel).copied().unwrap_or_default()
+    }
+
+    pub fn channels(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.0
+            .iter()
+            .map(|(channel, value)| (channel.as_str(), *value))
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, bool)> for Signals {
+    fn from_iter<I: IntoIterator<Item = (S, bool)>>(channels: I) -> Self {
+        Self(
+            channels
+                .into_iter()
+                .map(|(channel, value)| (channel.into(), value))
+                .collect(),
+        )
+    }
+}
+
+/// Drives `channel`, the value is set by the emitter or gate on the same entity.
+#[derive(Component, Clone, Debug)]
+#[require(Transform)]
+pub struct SignalEmitter {
+    pub channel: String,
+    value: bool,
+}
+
+impl SignalEmitter {
+    pub fn new(channel: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            value: false,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.value
+    }
+}
+
+/// Follows the value of `channel`, change detection on this component can be used to react to
+/// the channel turning on or off.
+#[derive(Component, Clone, Debug)]
+pub struct SignalReceiver {
+    pub channel: String,
+    value: bool,
+}
+
+impl SignalReceiver {
+    pub fn new(channel: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            value: false,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.value
+    }
+}
+
+/// Sets the value of an emitter without marking it as changed if the value stays the same.
+fn set_emitter_value(emitter: &mut Mut<SignalEmitter>, value: bool) {
+    if emitter.value != value {
+        emitter.value = value;
+    }
+}
+
+pub(crate) fn collect_signals(emitters: Query<&SignalEmitter>, mut signals: ResMut<Signals>) {
+    let mut values = HashMap::<String, bool>::default();
+    for emitter in &emitters {
+        *values.entry(emitter.channel.clone()).or_default() |= emitter.value;
+    }
+
+    if values != signals.0 {
+        signals.0 = values;
+    }
+}
+
+pub(crate) fn update_receivers(signals: Res<Signals>, mut receivers: Query<&mut SignalReceiver>) {
+    for mut receiver in &mut receivers {
+        let value = signals.is_on(&receiver.channel);
+        // Only touched on changes so that change detection reports actual signal changes
+        if receiver.value != value {
+            receiver.value = value;
+        }
+    }
+}
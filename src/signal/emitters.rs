@@ -0,0 +1,172 @@
+use avian2d::prelude::*;
+use bevy::{
+    ecs::{
+        entity::EntityHashSet,
+        world::{EntityRef, EntityWorldMut},
+    },
+    prelude::*,
+};
+use derive::TriggerMessage;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    interaction::{Interactable, InteractionsWith},
+    persistence::Persistent,
+    sector::{ShapeTrigger, TriggerChannel, TriggerTransition},
+};
+
+use super::{SignalEmitter, set_emitter_value};
+
+/// Toggles its [`SignalEmitter`] whenever it is interacted with.
+#[derive(Component)]
+#[require(Interactable = Interactable::new("Pull"))]
+pub struct Lever;
+
+/// Saved state of a [`Lever`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeverSnapshot {
+    pub on: bool,
+}
+
+impl Persistent for Lever {
+    const KEY: &'static str = "lever";
+
+    type State = LeverSnapshot;
+
+    fn save(entity: EntityRef) -> Option<Self::State> {
+        let on = entity.get::<SignalEmitter>()?.is_on();
+        Some(LeverSnapshot { on })
+    }
+
+    fn restore(mut entity: EntityWorldMut, snapshot: Self::State) {
+        if let Some(mut emitter) = entity.get_mut::<SignalEmitter>() {
+            set_emitter_value(&mut emitter, snapshot.on);
+        }
+    }
+}
+
+/// Turns its [`SignalEmitter`] on for `duration` seconds whenever it is interacted with.
+#[derive(Component)]
+#[require(Interactable = Interactable::new("Press"))]
+pub struct PushButton {
+    pub duration: f32,
+    /// Seconds until the button releases.
+    remaining: f32,
+}
+
+impl PushButton {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            duration,
+            remaining: 0.0,
+        }
+    }
+}
+
+/// Keeps its [`SignalEmitter`] on while anything is inside the zone of the [`ShapeTrigger`] on the
+/// same entity, see [`pressure_plate`].
+#[derive(Component, Default)]
+pub struct PressurePlate {
+    occupants: EntityHashSet,
+}
+
+#[derive(Message, TriggerMessage)]
+pub struct PressurePlateMessage {
+    transition: TriggerTransition,
+    trigger: Entity,
+    target: Entity,
+}
+
+/// Turns its [`SignalEmitter`] on for the first `on_duration` seconds of every `period`.
+#[derive(Component)]
+pub struct SignalTimer {
+    pub period: f32,
+    pub on_duration: f32,
+    elapsed: f32,
+}
+
+impl SignalTimer {
+    pub fn new(period: f32, on_duration: f32) -> Self {
+        Self {
+            period,
+            on_duration,
+            elapsed: 0.0,
+        }
+    }
+}
+
+pub(super) fn toggle_levers(
+    mut interactions: InteractionsWith<Lever>,
+    mut levers: Query<&mut SignalEmitter, With<Lever>>,
+) {
+    for interaction in interactions.read() {
+        if let Ok(mut emitter) = levers.get_mut(interaction.target) {
+            emitter.value = !emitter.value;
+        }
+    }
+}
+
+pub(super) fn press_buttons(
+    mut interactions: InteractionsWith<PushButton>,
+    mut buttons: Query<&mut PushButton>,
+) {
+    for interaction in interactions.read() {
+        if let Ok(mut button) = buttons.get_mut(interaction.target) {
+            button.remaining = button.duration;
+        }
+    }
+}
+
+pub(super) fn update_buttons(
+    time: Res<Time>,
+    mut buttons: Query<(&mut PushButton, &mut SignalEmitter)>,
+) {
+    for (mut button, mut emitter) in &mut buttons {
+        set_emitter_value(&mut emitter, button.remaining > 0.0);
+        button.remaining = (button.remaining - time.delta_secs()).max(0.0);
+    }
+}
+
+pub(super) fn update_pressure_plates(
+    mut messages: MessageReader<PressurePlateMessage>,
+    mut plates: Query<(&mut PressurePlate, &mut SignalEmitter)>,
+) {
+    for message in messages.read() {
+        let Ok((mut plate, _)) = plates.get_mut(message.trigger) else {
+            continue;
+        };
+
+        match message.transition {
+            TriggerTransition::Enter => {
+                plate.occupants.insert(message.target);
+            }
+            TriggerTransition::Exit => {
+                plate.occupants.remove(&message.target);
+            }
+            TriggerTransition::Stay => {}
+        }
+    }
+
+    for (plate, mut emitter) in &mut plates {
+        set_emitter_value(&mut emitter, !plate.occupants.is_empty());
+    }
+}
+
+pub(crate) fn update_signal_timers(
+    time: Res<Time>,
+    mut timers: Query<(&mut SignalTimer, &mut SignalEmitter)>,
+) {
+    for (mut timer, mut emitter) in &mut timers {
+        timer.elapsed = (timer.elapsed + time.delta_secs()) % timer.period.max(f32::EPSILON);
+        set_emitter_value(&mut emitter, timer.elapsed < timer.on_duration);
+    }
+}
+
+/// Pressure plate driving `channel` while anything on `mask` is inside the shape of the entity.
+pub fn pressure_plate(channel: impl Into<String>, mask: LayerMask) -> impl Bundle {
+    (
+        PressurePlate::default(),
+        SignalEmitter::new(channel),
+        ShapeTrigger::new().with_channel(TriggerChannel::new::<PressurePlateMessage>(mask)),
+    )
+}
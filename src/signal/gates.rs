@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{SignalEmitter, Signals, set_emitter_value};
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum GateKind {
+    /// On while every input is on.
+    And,
+    /// On while any input is on.
+    Or,
+    /// On while every input is off.
+    Not,
+    /// Turns on when the first input turns on and stays on until the second input turns on.
+    Latch,
+    /// Follows whether any input is on, the given number of seconds later.
+    Delay(f32),
+}
+
+/// Combines the channels in `inputs` into the channel of the [`SignalEmitter`] on the same entity.
+/// Gates read the channels of the previous update, so every gate in a chain adds a single update
+/// of latency.
+#[derive(Component, Clone, Debug)]
+pub struct SignalGate {
+    pub kind: GateKind,
+    pub inputs: Vec<String>,
+    /// Whether any input was on in the last update.
+    last_input: bool,
+    /// Input changes of a delay gate that haven't reached the output yet, along with the time they
+    /// are due at.
+    pending: VecDeque<(f32, bool)>,
+    elapsed: f32,
+}
+
+impl SignalGate {
+    pub fn new(kind: GateKind, inputs: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            kind,
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            last_input: false,
+            pending: VecDeque::new(),
+            elapsed: 0.0,
+        }
+    }
+
+    fn evaluate(&mut self, signals: &Signals, output: bool, delta: f32) -> bool {
+        let mut inputs = self.inputs.iter().map(|input| signals.is_on(input));
+
+        match self.kind {
+            GateKind::And => inputs.all(|input| input),
+            GateKind::Or => inputs.any(|input| input),
+            GateKind::Not => !inputs.any(|input| input),
+            GateKind::Latch => {
+                let set = inputs.next().unwrap_or_default();
+                let reset = inputs.next().unwrap_or_default();
+                (output || set) && !reset
+            }
+            GateKind::Delay(delay) => {
+                let input = inputs.any(|input| input);
+                self.elapsed += delta;
+
+                if input != self.last_input {
+                    self.pending.push_back((self.elapsed + delay, input));
+                    self.last_input = input;
+                }
+
+                let mut output = output;
+                while let Some(&(due, value)) = self.pending.front()
+                    && due <= self.elapsed
+                {
+                    output = value;
+                    self.pending.pop_front();
+                }
+                output
+            }
+        }
+    }
+}
+
+pub(super) fn evaluate_gates(
+    time: Res<Time>,
+    signals: Res<Signals>,
+    mut gates: Query<(&mut SignalGate, &mut SignalEmitter)>,
+) {
+    for (mut gate, mut emitter) in &mut gates {
+        let output = gate.evaluate(&signals, emitter.is_on(), time.delta_secs());
+        set_emitter_value(&mut emitter, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(channels: &[(&str, bool)]) -> Signals {
+        channels.iter().copied().collect()
+    }
+
+    #[test]
+    fn latch_stays_on_after_being_set_until_it_is_reset() {
+        let mut gate = SignalGate::new(GateKind::Latch, ["set", "reset"]);
+
+        let mut output = gate.evaluate(&signals(&[]), false, 0.1);
+        assert!(!output);
+
+        output = gate.evaluate(&signals(&[("set", true)]), output, 0.1);
+        assert!(output);
+        output = gate.evaluate(&signals(&[("set", false)]), output, 0.1);
+        assert!(output, "the latch turned off with its set input");
+
+        output = gate.evaluate(&signals(&[("reset", true)]), output, 0.1);
+        assert!(!output);
+        output = gate.evaluate(&signals(&[("reset", false)]), output, 0.1);
+        assert!(!output, "the latch turned on with its reset input");
+    }
+
+    #[test]
+    fn latch_reset_wins_over_set() {
+        let mut gate = SignalGate::new(GateKind::Latch, ["set", "reset"]);
+
+        let both = signals(&[("set", true), ("reset", true)]);
+
+        assert!(!gate.evaluate(&both, false, 0.1));
+        assert!(!gate.evaluate(&both, true, 0.1));
+    }
+
+    #[test]
+    fn delay_follows_its_inputs_after_the_delay() {
+        let mut gate = SignalGate::new(GateKind::Delay(1.0), ["input"]);
+        let on = signals(&[("input", true)]);
+        let off = signals(&[("input", false)]);
+
+        let mut output = gate.evaluate(&on, false, 0.5);
+        assert!(!output);
+        output = gate.evaluate(&on, output, 0.5);
+        assert!(!output);
+        output = gate.evaluate(&off, output, 0.5);
+        assert!(output, "the input turned on a second ago");
+        output = gate.evaluate(&off, output, 0.5);
+        assert!(output);
+        output = gate.evaluate(&off, output, 0.5);
+        assert!(!output, "the input turned off a second ago");
+    }
+
+    #[test]
+    fn delay_keeps_pulses_shorter_than_the_delay() {
+        let mut gate = SignalGate::new(GateKind::Delay(1.0), ["a", "b"]);
+        let on = signals(&[("b", true)]);
+        let off = signals(&[]);
+
+        let mut outputs = Vec::new();
+        let mut output = false;
+        for input in [&on, &off, &off, &off, &off, &off] {
+            output = gate.evaluate(input, output, 0.25);
+            outputs.push(output);
+        }
+
+        // The pulse from 0.25 to 0.5 seconds comes out from 1.25 to 1.5 seconds
+        assert_eq!(outputs, [false, false, false, false, true, false]);
+    }
+}
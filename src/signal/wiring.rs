@@ -0,0 +1,206 @@
+use avian2d::prelude::*;
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    persistence::PersistentId,
+    physics::ObjectLayer,
+    sector::{RectangleShape, TriggerShape},
+};
+
+use super::{
+    GateKind, Lever, PushButton, SignalEmitter, SignalGate, SignalReceiver, SignalTimer,
+    pressure_plate,
+};
+
+const SIGNAL_ON_COLOR: Color = Color::srgb(0.2, 0.8, 0.3);
+const SIGNAL_OFF_COLOR: Color = Color::srgb(0.6, 0.2, 0.2);
+
+/// Emitters, gates and receivers of a level, loaded from `.wiring.ron` files.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct Wiring {
+    #[serde(default)]
+    pub emitters: Vec<EmitterDefinition>,
+    #[serde(default)]
+    pub gates: Vec<GateDefinition>,
+    #[serde(default)]
+    pub receivers: Vec<ReceiverDefinition>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EmitterDefinition {
+    pub kind: EmitterKind,
+    pub channel: String,
+    pub position: Vec2,
+    /// [`PersistentId`] of the emitter, only emitters with one keep their state across reloads.
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub enum EmitterKind {
+    Lever,
+    Button {
+        duration: f32,
+    },
+    /// Pressed by characters on the player layer.
+    PressurePlate {
+        half_size: Vec2,
+    },
+    Timer {
+        period: f32,
+        on_duration: f32,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GateDefinition {
+    pub kind: GateKind,
+    pub inputs: Vec<String>,
+    pub output: String,
+    /// Only used to draw the wiring.
+    #[serde(default)]
+    pub position: Vec2,
+}
+
+/// Attaches a [`SignalReceiver`] to the entity with the [`Name`] `target`.
+#[derive(Deserialize, Debug)]
+pub struct ReceiverDefinition {
+    pub target: String,
+    pub channel: String,
+}
+
+#[derive(Default, TypePath)]
+pub struct WiringLoader;
+
+impl AssetLoader for WiringLoader {
+    type Asset = Wiring;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["wiring.ron"]
+    }
+}
+
+/// Spawns the wiring once it has loaded, the entities receivers are attached to have to be spawned
+/// by then.
+#[derive(Component)]
+pub struct LevelWiring(pub Handle<Wiring>);
+
+pub(super) fn spawn_level_wiring(
+    wirings: Res<Assets<Wiring>>,
+    levels: Query<(Entity, &LevelWiring)>,
+    names: Query<(Entity, &Name)>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (level, LevelWiring(handle)) in &levels {
+        let Some(wiring) = wirings.get(handle) else {
+            continue;
+        };
+        commands.entity(level).remove::<LevelWiring>();
+
+        for definition in &wiring.emitters {
+            let mut emitter = commands.spawn((
+                SignalEmitter::new(&definition.channel),
+                Transform::from_translation(definition.position.extend(0.0)),
+                MeshMaterial2d(materials.add(SIGNAL_OFF_COLOR)),
+            ));
+            let interactable_layers = CollisionLayers::new(
+                LayerMask(ObjectLayer::Interactable.to_bits()),
+                LayerMask(ObjectLayer::None.to_bits()),
+            );
+
+            match definition.kind {
+                EmitterKind::Lever => emitter.insert((
+                    Lever,
+                    Mesh2d(meshes.add(Rectangle::new(8.0, 24.0))),
+                    RigidBody::Static,
+                    Collider::rectangle(8.0, 24.0),
+                    interactable_layers,
+                )),
+                EmitterKind::Button { duration } => emitter.insert((
+                    PushButton::new(duration),
+                    Mesh2d(meshes.add(Circle::new(8.0))),
+                    RigidBody::Static,
+                    Collider::circle(8.0),
+                    interactable_layers,
+                )),
+                EmitterKind::PressurePlate { half_size } => emitter.insert((
+                    pressure_plate(
+                        &definition.channel,
+                        LayerMask(ObjectLayer::Player.to_bits()),
+                    ),
+                    RectangleShape::new(half_size).into_bundle(&mut meshes),
+                    // Drawn below characters
+                    Transform::from_translation(definition.position.extend(-1.0)),
+                )),
+                EmitterKind::Timer {
+                    period,
+                    on_duration,
+                } => emitter.insert((
+                    SignalTimer::new(period, on_duration),
+                    Mesh2d(meshes.add(Circle::new(6.0))),
+                )),
+            };
+
+            // Inserted last so that the emitter is complete by the time its state is restored
+            if let Some(id) = &definition.id {
+                emitter.insert(PersistentId::new(id));
+            }
+        }
+
+        for definition in &wiring.gates {
+            commands.spawn((
+                SignalGate::new(definition.kind, definition.inputs.iter().cloned()),
+                SignalEmitter::new(&definition.output),
+                Transform::from_translation(definition.position.extend(0.0)),
+            ));
+        }
+
+        for definition in &wiring.receivers {
+            match names
+                .iter()
+                .find(|(_, name)| name.as_str() == definition.target)
+            {
+                Some((target, _)) => {
+                    commands
+                        .entity(target)
+                        .insert(SignalReceiver::new(&definition.channel));
+                }
+                None => warn!("No entity named {} to receive signals", definition.target),
+            }
+        }
+    }
+}
+
+pub(super) fn update_emitter_colors(
+    emitters: Query<(&SignalEmitter, &MeshMaterial2d<ColorMaterial>), Changed<SignalEmitter>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (emitter, material) in &emitters {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = if emitter.is_on() {
+                SIGNAL_ON_COLOR
+            } else {
+                SIGNAL_OFF_COLOR
+            };
+        }
+    }
+}
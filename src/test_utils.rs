@@ -0,0 +1,28 @@
+//! Helpers shared between the unit tests of several modules.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::sector::{SectorParameters, TriggerContext, TriggerTransition};
+
+pub fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+}
+
+/// Entering detection of `target` by a quarter circle sector with a radius of 100.
+pub fn context(target: Entity, distance: f32, angle: f32) -> TriggerContext {
+    TriggerContext {
+        transition: TriggerTransition::Enter,
+        trigger: Entity::PLACEHOLDER,
+        target,
+        distance,
+        angle,
+        contact_point: Vec2::from_angle(angle) * distance,
+        parameters: SectorParameters {
+            radius: 100.0,
+            arc_angle: PI / 2.0,
+            center_angle: 0.0,
+        },
+    }
+}
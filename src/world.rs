@@ -11,6 +11,7 @@ pub enum WorldType {
     CustomGeometry,
 }
 
+#[derive(Default)]
 pub struct WorldPlugin {
     world_type: WorldType,
 }
@@ -21,14 +22,6 @@ impl WorldPlugin {
     }
 }
 
-impl Default for WorldPlugin {
-    fn default() -> Self {
-        Self {
-            world_type: WorldType::default(),
-        }
-    }
-}
-
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
         match self.world_type {
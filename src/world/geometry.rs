@@ -5,22 +5,45 @@ use avian2d::prelude::*;
 use bevy::prelude::*;
 
 use crate::{
-    objects::entities::{Door, DoorColors, DoorShader},
+    camera::{CameraLevelBounds, CameraRegion},
+    objects::entities::{
+        AutomaticDoor, Door, DoorMotion, DoorPlayerPosition, DoorSensorZone, DoorShader, KeyPickup,
+        LockRequirement,
+    },
+    persistence::PersistentId,
     physics::ObjectLayer,
+    signal::LevelWiring,
 };
 
 const WALL_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+const KEY_COLOR: Color = Color::srgb(0.9, 0.75, 0.2);
 
 #[derive(Component)]
 pub struct Wall;
 
 pub fn setup_geometry(
-    door_colors: Res<DoorColors>,
+    asset_server: Res<AssetServer>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut color_materials: ResMut<Assets<ColorMaterial>>,
     mut door_materials: ResMut<Assets<DoorShader>>,
+    door_player_position: Option<Res<DoorPlayerPosition>>,
 ) {
+    // Camera confinement, the rooms overlap around the doors so the camera doesn't switch rooms
+    // while the player is standing in a doorway
+    commands.insert_resource(CameraLevelBounds(Some(Rect::new(
+        -312.5, -387.5, 312.5, 387.5,
+    ))));
+    commands.spawn(CameraRegion::rectangle(Rect::new(
+        -312.5, 112.5, 312.5, 387.5,
+    )));
+    commands.spawn(CameraRegion::rectangle(Rect::new(
+        -312.5, -137.5, 312.5, 137.5,
+    )));
+    commands.spawn(CameraRegion::rectangle(Rect::new(
+        -312.5, -387.5, 312.5, -112.5,
+    )));
+
     // Left walls
     commands.spawn(rectangle_wall_bundle(
         vec2(25.0, 275.0),
@@ -64,14 +87,26 @@ pub fn setup_geometry(
     ));
 
     spawn_door(
+        "east_door",
         vec2(17.5, 125.0),
         vec2(300.0, 0.0),
-        vec2(0.0, 120.0),
         0.0,
-        &door_colors,
+        Door::new(DoorMotion::Slide {
+            offset: vec2(0.0, 120.0),
+        }),
+        Some(
+            AutomaticDoor::new(
+                DoorSensorZone::Rectangle {
+                    half_size: vec2(60.0, 62.5),
+                },
+                LayerMask(ObjectLayer::Player.to_bits()),
+            )
+            .with_close_delay(0.75),
+        ),
         &mut commands,
         &mut meshes,
         &mut door_materials,
+        door_player_position.as_deref(),
     );
 
     commands.spawn(rectangle_wall_bundle(
@@ -123,15 +158,24 @@ pub fn setup_geometry(
         &mut color_materials,
     ));
 
+    // Hinged doors are a bit narrower than their doorway, so that the corners at the hinge don't
+    // scrape along the wall while swinging
     spawn_door(
-        vec2(125.0, 17.5),
+        "north_door",
+        vec2(115.0, 15.0),
         vec2(0.0, 125.0),
-        vec2(-120.0, 0.0),
         0.0,
-        &door_colors,
+        Door::new(DoorMotion::Hinge {
+            pivot: vec2(-50.0, 0.0),
+            angle: f32::to_radians(90.0),
+            swing_away: true,
+        })
+        .with_lock(LockRequirement::Key("north_key".to_owned())),
+        None,
         &mut commands,
         &mut meshes,
         &mut door_materials,
+        door_player_position.as_deref(),
     );
 
     commands.spawn(rectangle_wall_bundle(
@@ -151,15 +195,34 @@ pub fn setup_geometry(
     ));
 
     spawn_door(
+        "south_door",
         vec2(125.0, 17.5),
         vec2(0.0, -125.0),
-        vec2(120.0, 0.0),
         0.0,
-        &door_colors,
+        Door::new(DoorMotion::Slide {
+            offset: vec2(120.0, 0.0),
+        }),
+        None,
         &mut commands,
         &mut meshes,
         &mut door_materials,
+        door_player_position.as_deref(),
     );
+
+    // Unlocks the north door
+    commands.spawn((
+        KeyPickup("north_key".to_owned()),
+        Mesh2d(meshes.add(Rectangle::new(12.0, 6.0))),
+        MeshMaterial2d(color_materials.add(KEY_COLOR)),
+        Transform::from_xyz(200.0, 60.0, 0.0),
+        Collider::rectangle(12.0, 6.0),
+        CollisionLayers::new(
+            LayerMask(ObjectLayer::Interactable.to_bits()),
+            LayerMask(ObjectLayer::None.to_bits()),
+        ),
+    ));
+
+    commands.spawn(LevelWiring(asset_server.load("levels/test.wiring.ron")));
 }
 
 fn rectangle_wall_bundle(
@@ -184,19 +247,24 @@ fn rectangle_wall_bundle(
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_door(
+    name: &str,
     size: Vec2,
     position: Vec2,
-    open_offset: Vec2,
     // Degrees
     angle: f32,
-    door_colors: &Res<DoorColors>,
+    door: Door,
+    automatic: Option<AutomaticDoor>,
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<DoorShader>>,
+    player_position: Option<&DoorPlayerPosition>,
 ) {
     let door_entity_id = commands
         .spawn((
+            Name::new(name.to_owned()),
+            PersistentId::new(format!("custom_geometry/{name}")),
             Mesh2d(meshes.add(Rectangle::new(size.x, size.y))),
             Transform::from_xyz(position.x, position.y, 0.0)
                 .with_rotation(Quat::from_rotation_z(f32::to_radians(angle))),
@@ -205,10 +273,16 @@ fn spawn_door(
                 LayerMask(ObjectLayer::Door.to_bits()),
                 LayerMask(ObjectLayer::None.to_bits()),
             ),
-            Door::new(door_colors.fill_color, door_colors.focus_color, open_offset),
+            door,
         ))
         .id();
-    commands.entity(door_entity_id).insert(MeshMaterial2d(
-        materials.add(DoorShader::new(door_colors.fill_color, door_entity_id)),
-    ));
+
+    if let Some(player_position) = player_position {
+        commands.entity(door_entity_id).insert(MeshMaterial2d(
+            materials.add(DoorShader::new(size, player_position)),
+        ));
+    }
+    if let Some(automatic) = automatic {
+        commands.entity(door_entity_id).insert(automatic);
+    }
 }